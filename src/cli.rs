@@ -9,8 +9,9 @@ use std::path::PathBuf;
     about = "Identify which package manager installed a command"
 )]
 pub struct Cli {
-    /// The command to investigate
-    pub command: String,
+    /// The command(s) to investigate
+    #[arg(value_name = "COMMAND", required_unless_present = "inventory")]
+    pub commands: Vec<String>,
 
     /// Output format
     #[arg(short, long, value_enum, default_value = "text")]
@@ -24,6 +25,18 @@ pub struct Cli {
     #[arg(short = 'i', long)]
     pub info: bool,
 
+    /// Report every matching package manager, ranked by confidence
+    #[arg(long)]
+    pub all_matches: bool,
+
+    /// Show every copy of the command on PATH, marking the one that runs
+    #[arg(short, long, conflicts_with = "all_matches")]
+    pub all: bool,
+
+    /// Inventory every executable on PATH, grouped by package manager
+    #[arg(long, conflicts_with_all = ["commands", "all", "all_matches"])]
+    pub inventory: bool,
+
     /// Verbose output (show detection steps)
     #[arg(short, long)]
     pub verbose: bool,
@@ -0,0 +1,126 @@
+use crate::package_managers::{serialize_path, DetectionResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Every executable on PATH grouped by the package manager that installed it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Inventory {
+    /// Total number of executables analyzed
+    pub total: usize,
+    /// One group per detected package manager, largest first
+    pub managers: Vec<ManagerGroup>,
+    /// Executables no detector recognised
+    pub unknown: Vec<InventoryEntry>,
+}
+
+/// Commands attributed to a single package manager.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManagerGroup {
+    pub manager_id: String,
+    pub manager_name: String,
+    pub count: usize,
+    pub commands: Vec<InventoryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InventoryEntry {
+    pub command: String,
+    pub package_name: Option<String>,
+    #[serde(serialize_with = "serialize_path")]
+    pub path: PathBuf,
+}
+
+impl Inventory {
+    /// Group `(command name, detection result)` pairs by package manager.
+    pub fn from_results(results: Vec<(String, DetectionResult)>) -> Self {
+        let total = results.len();
+        let mut groups: BTreeMap<String, ManagerGroup> = BTreeMap::new();
+        let mut unknown = Vec::new();
+
+        for (command, result) in results {
+            let entry = InventoryEntry {
+                command,
+                package_name: result.package_name,
+                path: result.command_path,
+            };
+
+            if result.manager_id == "unknown" {
+                unknown.push(entry);
+                continue;
+            }
+
+            groups
+                .entry(result.manager_id.clone())
+                .or_insert_with(|| ManagerGroup {
+                    manager_id: result.manager_id,
+                    manager_name: result.manager_name,
+                    count: 0,
+                    commands: Vec::new(),
+                })
+                .commands
+                .push(entry);
+        }
+
+        let mut managers: Vec<ManagerGroup> = groups
+            .into_values()
+            .map(|mut group| {
+                group.count = group.commands.len();
+                group
+            })
+            .collect();
+        // Largest groups first; BTreeMap order breaks ties by manager id
+        managers.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+        Self {
+            total,
+            managers,
+            unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package_managers::{Confidence, Verification};
+
+    fn result(manager_id: &str, path: &str) -> DetectionResult {
+        DetectionResult {
+            manager_id: manager_id.to_string(),
+            manager_name: manager_id.to_uppercase(),
+            package_name: None,
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: PathBuf::from(path),
+            resolved_path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_groups_by_manager() {
+        let inventory = Inventory::from_results(vec![
+            (
+                "git".to_string(),
+                result("homebrew", "/opt/homebrew/bin/git"),
+            ),
+            ("rg".to_string(), result("cargo", "/home/u/.cargo/bin/rg")),
+            (
+                "node".to_string(),
+                result("homebrew", "/opt/homebrew/bin/node"),
+            ),
+            ("mystery".to_string(), result("unknown", "/opt/x/mystery")),
+        ]);
+
+        assert_eq!(inventory.total, 4);
+        assert_eq!(inventory.managers.len(), 2);
+        assert_eq!(inventory.managers[0].manager_id, "homebrew");
+        assert_eq!(inventory.managers[0].count, 2);
+        assert_eq!(inventory.managers[1].manager_id, "cargo");
+        assert_eq!(inventory.unknown.len(), 1);
+        assert_eq!(inventory.unknown[0].command, "mystery");
+    }
+}
@@ -1,14 +1,32 @@
+pub mod inventory;
 pub mod path_resolver;
 pub mod symlink_analyzer;
 
 use crate::error::Result;
-use crate::package_managers::{DetectionContext, DetectionResult, PackageManagerRegistry};
+use crate::package_managers::{
+    rank_candidates, DetectionCandidate, DetectionContext, DetectionResult, LinkKind,
+    PackageManagerRegistry, Verification,
+};
 use crate::platform::Platform;
+use inventory::Inventory;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// One copy of a command found on PATH (see `--all`).
+#[derive(Debug, Clone, Serialize)]
+pub struct PathCopy {
+    /// Whether this is the copy that runs (the first one on PATH)
+    pub active: bool,
+    #[serde(flatten)]
+    pub result: DetectionResult,
+}
 
 /// Main detection orchestrator
 pub struct Detector {
     registry: PackageManagerRegistry,
     verbose: bool,
+    verify: bool,
+    info: bool,
 }
 
 impl Detector {
@@ -16,11 +34,114 @@ impl Detector {
         Self {
             registry: PackageManagerRegistry::new(),
             verbose,
+            verify: true,
+            info: false,
         }
     }
 
+    /// Load additional detectors from a custom rules file.
+    pub fn with_database(mut self, path: &Path) -> Result<Self> {
+        self.registry = PackageManagerRegistry::with_database(path)?;
+        Ok(self)
+    }
+
+    /// Enable or disable the verification phase (enabled by default).
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Collect detailed package metadata for the detected package.
+    pub fn with_info(mut self, info: bool) -> Self {
+        self.info = info;
+        self
+    }
+
     /// Detect which package manager installed the given command.
     pub fn detect(&self, command: &str) -> Result<DetectionResult> {
+        let context = self.resolve(command)?;
+        Ok(self.detect_context(&context))
+    }
+
+    /// Detect the package manager of every copy of the command on PATH, in
+    /// PATH order. The first copy is the one that actually runs.
+    pub fn detect_all_copies(&self, command: &str) -> Result<Vec<PathCopy>> {
+        if self.verbose {
+            eprintln!("Resolving every path for '{}'...", command);
+        }
+        let paths = path_resolver::resolve_all_commands(command)?;
+
+        Ok(paths
+            .into_iter()
+            .enumerate()
+            .map(|(i, command_path)| {
+                if self.verbose {
+                    eprintln!("Found at {}", command_path.display());
+                }
+                let context = self.context_for(command, command_path);
+                PathCopy {
+                    active: i == 0,
+                    result: self.detect_context(&context),
+                }
+            })
+            .collect())
+    }
+
+    /// Detect every executable in every PATH directory and group the results
+    /// by package manager.
+    pub fn inventory(&self) -> Inventory {
+        let results = path_resolver::list_path_executables()
+            .into_iter()
+            .map(|(command, command_path)| {
+                if self.verbose {
+                    eprintln!("Analyzing {}...", command_path.display());
+                }
+                let context = self.context_for(&command, command_path);
+                let result = self.detect_context(&context);
+                (command, result)
+            })
+            .collect();
+        Inventory::from_results(results)
+    }
+
+    fn detect_context(&self, context: &DetectionContext) -> DetectionResult {
+        // Step 4: Try each package manager detector
+        if let Some(mut result) = self.registry.detect(context, self.verbose) {
+            // Step 5: Confirm the match with the package manager's metadata
+            self.enrich(context, &mut result);
+            return result;
+        }
+
+        // Step 6: Return unknown if no detector matched
+        DetectionResult {
+            manager_id: "unknown".to_string(),
+            manager_name: "Unknown".to_string(),
+            package_name: None,
+            version: None,
+            details: Default::default(),
+            confidence: crate::package_managers::Confidence::Uncertain,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: context.command_path.clone(),
+            resolved_path: context.resolved_path.clone(),
+        }
+    }
+
+    /// Detect every package manager that matches the given command, ranked
+    /// from most to least likely. Returns an empty list if nothing matched.
+    pub fn detect_all(&self, command: &str) -> Result<Vec<DetectionCandidate>> {
+        let context = self.resolve(command)?;
+
+        let mut candidates = self.registry.detect_all(&context, self.verbose);
+        for candidate in &mut candidates {
+            self.enrich(&context, &mut candidate.result);
+        }
+        rank_candidates(&mut candidates);
+        Ok(candidates)
+    }
+
+    /// Resolve a command and build its detection context.
+    fn resolve(&self, command: &str) -> Result<DetectionContext> {
         // Step 1: Resolve command to path
         if self.verbose {
             eprintln!("Resolving path for '{}'...", command);
@@ -30,6 +151,11 @@ impl Detector {
             eprintln!("Found at {}", command_path.display());
         }
 
+        Ok(self.context_for(command, command_path))
+    }
+
+    /// Build the detection context for a resolved command path.
+    fn context_for(&self, command: &str, command_path: PathBuf) -> DetectionContext {
         // Step 2: Follow symlinks
         let symlink_chain = symlink_analyzer::follow_symlinks(command_path.clone());
         let resolved_path = symlink_chain
@@ -41,30 +167,37 @@ impl Detector {
             eprintln!("Following symlink to {}", resolved_path.display());
         }
 
+        // Step 2b: Follow shebangs and wrapper scripts
+        let script_links = symlink_analyzer::follow_scripts(&resolved_path);
+        if self.verbose {
+            for link in &script_links {
+                let how = match link.kind {
+                    LinkKind::InterpretedBy => "Interpreted by",
+                    LinkKind::Execs => "Wrapper execs",
+                };
+                eprintln!("{} {}", how, link.path.display());
+            }
+        }
+
         // Step 3: Create detection context
-        let context = DetectionContext {
+        DetectionContext {
             command_name: command.to_string(),
-            command_path: command_path.clone(),
+            command_path,
             symlink_chain,
-            resolved_path: resolved_path.clone(),
+            resolved_path,
             platform: Platform::current(),
-        };
-
-        // Step 4: Try each package manager detector
-        if let Some(result) = self.registry.detect(&context, self.verbose) {
-            return Ok(result);
+            script_links,
         }
+    }
 
-        // Step 5: Return unknown if no detector matched
-        Ok(DetectionResult {
-            manager_id: "unknown".to_string(),
-            manager_name: "Unknown".to_string(),
-            package_name: None,
-            version: None,
-            confidence: crate::package_managers::Confidence::Uncertain,
-            command_path,
-            resolved_path,
-        })
+    /// Run the optional verification and info phases on a matched result.
+    fn enrich(&self, context: &DetectionContext, result: &mut DetectionResult) {
+        if self.verify {
+            self.registry.verify(context, result, self.verbose);
+        }
+        if self.info {
+            self.registry.info(context, result, self.verbose);
+        }
     }
 }
 
@@ -72,3 +205,8 @@ impl Detector {
 pub fn detect_command(command: &str, verbose: bool) -> Result<DetectionResult> {
     Detector::new(verbose).detect(command)
 }
+
+/// Convenience function for `Detector::detect_all`
+pub fn detect_all(command: &str, verbose: bool) -> Result<Vec<DetectionCandidate>> {
+    Detector::new(verbose).detect_all(command)
+}
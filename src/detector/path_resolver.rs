@@ -1,11 +1,109 @@
 use crate::error::{Result, WhyError};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 /// Resolve a command name to its absolute path using the system PATH.
 pub fn resolve_command(name: &str) -> Result<PathBuf> {
     which::which(name).map_err(|_| WhyError::CommandNotFound(name.to_string()))
 }
 
+/// Resolve a command name to every matching executable on the system PATH,
+/// in PATH order (the first one is what the shell runs). PATH directories that
+/// are aliases of each other (e.g. `/bin` symlinked to `/usr/bin`) are
+/// reported once; symlinks in distinct directories are kept.
+pub fn resolve_all_commands(name: &str) -> Result<Vec<PathBuf>> {
+    resolve_all_in(name, std::env::var_os("PATH"))
+}
+
+fn resolve_all_in(name: &str, path_var: Option<OsString>) -> Result<Vec<PathBuf>> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let matches = which::which_in_all(name, path_var, cwd)
+        .map_err(|_| WhyError::CommandNotFound(name.to_string()))?;
+
+    let mut seen = HashSet::new();
+    let paths: Vec<PathBuf> = matches
+        .filter(|path| seen.insert(canonical_location(path)))
+        .collect();
+
+    if paths.is_empty() {
+        return Err(WhyError::CommandNotFound(name.to_string()));
+    }
+    Ok(paths)
+}
+
+/// List every executable in every PATH directory as `(command name, path)`,
+/// in PATH order. Shadowed copies are included; aliased directories are
+/// listed once.
+pub fn list_path_executables() -> Vec<(String, PathBuf)> {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    list_executables_in(std::env::split_paths(&path_var))
+}
+
+fn list_executables_in(dirs: impl Iterator<Item = PathBuf>) -> Vec<(String, PathBuf)> {
+    let mut seen_dirs = HashSet::new();
+    let mut executables = Vec::new();
+
+    for dir in dirs {
+        let canonical = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if !seen_dirs.insert(canonical) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        let mut found: Vec<(String, PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|path| is_executable(path))
+            .filter_map(|path| Some((command_name(&path)?, path)))
+            .collect();
+        found.sort();
+        executables.extend(found);
+    }
+
+    executables
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            let ext = format!(".{}", ext.to_string_lossy());
+            pathext.split(';').any(|e| e.eq_ignore_ascii_case(&ext))
+        })
+}
+
+/// The name a command is invoked by (without `.exe` etc. on Windows).
+fn command_name(path: &Path) -> Option<String> {
+    #[cfg(windows)]
+    let name = path.file_stem();
+    #[cfg(not(windows))]
+    let name = path.file_name();
+    name.map(|n| n.to_string_lossy().to_string())
+}
+
+/// Canonicalize the containing directory but not the file itself.
+fn canonical_location(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) => dir
+            .canonicalize()
+            .unwrap_or_else(|_| dir.to_path_buf())
+            .join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,4 +122,80 @@ mod tests {
         let result = resolve_command("definitely_not_a_real_command_xyz_123");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_resolve_all_nonexistent_command() {
+        let result = resolve_all_commands("definitely_not_a_real_command_xyz_123");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_all_in_path_order() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut dirs = Vec::new();
+        for name in ["first", "second", "empty"] {
+            let bin = dir.path().join(name);
+            std::fs::create_dir(&bin).unwrap();
+            if name != "empty" {
+                let tool = bin.join("tool");
+                std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+                std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            dirs.push(bin);
+        }
+        // Listing a directory twice (or via a symlinked alias) must not
+        // report the same file twice
+        dirs.push(dirs[0].clone());
+        let alias = dir.path().join("alias");
+        std::os::unix::fs::symlink(&dirs[1], &alias).unwrap();
+        dirs.push(alias);
+
+        let path_var = std::env::join_paths(&dirs).unwrap();
+        let found = resolve_all_in("tool", Some(path_var)).unwrap();
+        assert_eq!(found, vec![dirs[0].join("tool"), dirs[1].join("tool")]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_all_first_matches_resolve() {
+        let all = resolve_all_commands("ls").unwrap();
+        assert_eq!(all[0], resolve_command("ls").unwrap());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_list_executables_in() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        for bin in [&first, &second] {
+            std::fs::create_dir(bin).unwrap();
+        }
+        for (bin, name, mode) in [
+            (&first, "zeta", 0o755),
+            (&first, "alpha", 0o755),
+            (&first, "data.txt", 0o644),
+            (&second, "alpha", 0o755),
+        ] {
+            let path = bin.join(name);
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let dirs = vec![first.clone(), second.clone(), first.clone()];
+        let found = list_executables_in(dirs.into_iter());
+        assert_eq!(
+            found,
+            vec![
+                ("alpha".to_string(), first.join("alpha")),
+                ("zeta".to_string(), first.join("zeta")),
+                ("alpha".to_string(), second.join("alpha")),
+            ]
+        );
+    }
 }
@@ -1,4 +1,6 @@
+use crate::package_managers::{LinkKind, ScriptLink};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 /// Follow the symlink chain from a path and return all paths in the chain.
@@ -31,6 +33,136 @@ pub fn follow_symlinks(path: PathBuf) -> Vec<PathBuf> {
     chain
 }
 
+/// Longest part of a file read when looking for a shebang or `exec` line.
+const SCRIPT_HEAD_LEN: u64 = 8 * 1024;
+
+/// Wrapper scripts followed before giving up.
+const MAX_SCRIPT_DEPTH: usize = 8;
+
+/// Shells whose scripts are checked for an `exec` line.
+const SHELLS: &[&str] = &["sh", "bash", "dash", "ksh", "zsh"];
+
+/// Follow a script to the programs it hands off to: the interpreter in its
+/// shebang, or the target of a wrapper's `exec` line. Wrapper targets are
+/// followed in turn; an interpreter ends the chain.
+pub fn follow_scripts(path: &Path) -> Vec<ScriptLink> {
+    let mut links: Vec<ScriptLink> = Vec::new();
+    let mut current = path.to_path_buf();
+    let mut seen = std::collections::HashSet::new();
+
+    while seen.insert(current.clone()) && links.len() < MAX_SCRIPT_DEPTH {
+        let Some(link) = script_link(&current) else {
+            break;
+        };
+        current = link.path.clone();
+        let kind = link.kind;
+        links.push(link);
+        if kind == LinkKind::InterpretedBy {
+            break;
+        }
+    }
+
+    links
+}
+
+/// The next link of a single script, if `path` is one.
+fn script_link(path: &Path) -> Option<ScriptLink> {
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(SCRIPT_HEAD_LEN)
+        .read_to_end(&mut head)
+        .ok()?;
+    if !head.starts_with(b"#!") {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&head);
+    let mut lines = text.lines();
+    let interpreter = parse_shebang(&lines.next()?[2..])?;
+
+    let is_shell = interpreter
+        .file_name()
+        .is_some_and(|name| SHELLS.iter().any(|shell| name == *shell));
+    if is_shell {
+        if let Some(link) = lines.find_map(parse_exec_line) {
+            return Some(link);
+        }
+    }
+    Some(ScriptLink {
+        kind: LinkKind::InterpretedBy,
+        path: interpreter,
+    })
+}
+
+/// The interpreter named by a shebang line (without the `#!`). `env` forms
+/// (`/usr/bin/env python3`, `env -S node --flag`) are looked up on PATH.
+fn parse_shebang(line: &str) -> Option<PathBuf> {
+    let mut words = line.split_whitespace();
+    let interpreter = PathBuf::from(words.next()?);
+    if interpreter.file_name().is_none_or(|name| name != "env") {
+        return Some(interpreter);
+    }
+
+    // Skip options and VAR=value assignments
+    let program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    let program = Path::new(program);
+    if program.is_absolute() {
+        Some(program.to_path_buf())
+    } else {
+        which::which(program).ok()
+    }
+}
+
+/// A shell line handing off to an absolute path: `exec "/path/to/real" "$@"`,
+/// or pip's long-shebang trick `'''exec' "/path/to/python" "$0" "$@"`, where
+/// the script re-runs itself under the given interpreter.
+fn parse_exec_line(line: &str) -> Option<ScriptLink> {
+    let line = line.trim();
+    let rest = line
+        .strip_prefix("'''exec'")
+        .or_else(|| line.strip_prefix("exec"))
+        .filter(|rest| rest.starts_with(char::is_whitespace))?;
+    let words = shell_words(rest);
+    let target = words.first()?;
+    // Only literal paths; anything built from variables is not followed
+    if target.contains(['$', '`']) || !Path::new(target).is_absolute() {
+        return None;
+    }
+
+    let kind = if words[1..].iter().any(|word| word == "$0") {
+        LinkKind::InterpretedBy
+    } else {
+        LinkKind::Execs
+    };
+    Some(ScriptLink {
+        kind,
+        path: PathBuf::from(target),
+    })
+}
+
+/// Split a line into words, removing single and double quotes. Good enough
+/// for the literal paths wrapper scripts use; no escapes or expansions.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c == '#' && word.is_none() => break,
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
 /// Get the final resolved path after following all symlinks.
 pub fn resolve_final_path(path: &Path) -> PathBuf {
     path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
@@ -120,4 +252,119 @@ mod tests {
         let chain = follow_symlinks(path.clone());
         assert_eq!(chain.len(), 1);
     }
+
+    fn link(kind: LinkKind, path: &str) -> ScriptLink {
+        ScriptLink {
+            kind,
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_follows_shebang() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("black");
+        std::fs::write(
+            &script,
+            "#!/home/user/.local/share/virtualenvs/x/bin/python -u\nimport black\n",
+        )
+        .unwrap();
+        assert_eq!(
+            follow_scripts(&script),
+            vec![link(
+                LinkKind::InterpretedBy,
+                "/home/user/.local/share/virtualenvs/x/bin/python"
+            )]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env_shebang() {
+        assert_eq!(
+            parse_shebang("/usr/bin/env -S PYTHONUNBUFFERED=1 /opt/py/bin/python3 -u"),
+            Some(PathBuf::from("/opt/py/bin/python3"))
+        );
+        assert_eq!(parse_shebang("/usr/bin/env no-such-interpreter-xyz"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env_shebang_searches_path() {
+        let sh = parse_shebang("/usr/bin/env sh").unwrap();
+        assert!(sh.is_absolute());
+        assert!(sh.ends_with("sh"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pip_long_shebang() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("black");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n'''exec' \"/very/long path/venv/bin/python\" \"$0\" \"$@\"\n' '''\n\
+             # -*- coding: utf-8 -*-\nimport sys\n",
+        )
+        .unwrap();
+        assert_eq!(
+            follow_scripts(&script),
+            vec![link(
+                LinkKind::InterpretedBy,
+                "/very/long path/venv/bin/python"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_follows_exec_wrapper() {
+        let dir = tempdir().unwrap();
+        let real = dir.path().join("libexec/tool");
+        std::fs::create_dir_all(real.parent().unwrap()).unwrap();
+        std::fs::write(&real, "#!/usr/local/bin/node\nrequire('./cli')\n").unwrap();
+        let wrapper = dir.path().join("tool");
+        std::fs::write(
+            &wrapper,
+            format!(
+                "#!/bin/bash\n# Generated wrapper\nexport TOOL_HOME=/opt\nexec \"{}\" \"$@\"\n",
+                real.display()
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            follow_scripts(&wrapper),
+            vec![
+                ScriptLink {
+                    kind: LinkKind::Execs,
+                    path: real,
+                },
+                link(LinkKind::InterpretedBy, "/usr/local/bin/node"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_exec_of_variables() {
+        let dir = tempdir().unwrap();
+        let wrapper = dir.path().join("tool");
+        std::fs::write(
+            &wrapper,
+            "#!/bin/sh\nexec \"$(dirname \"$0\")/real\" \"$@\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            follow_scripts(&wrapper),
+            vec![link(LinkKind::InterpretedBy, "/bin/sh")]
+        );
+    }
+
+    #[test]
+    fn test_ignores_non_scripts() {
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("tool");
+        std::fs::write(&binary, b"\x7fELF\x02\x01\x01").unwrap();
+        assert!(follow_scripts(&binary).is_empty());
+        assert!(follow_scripts(Path::new("/nonexistent/tool")).is_empty());
+    }
 }
@@ -33,6 +33,9 @@ pub enum WhyError {
     #[error("Could not determine package manager for '{0}'")]
     UnknownSource(String),
 
+    #[error("Invalid database file {}: {message}", path.display())]
+    DatabaseError { path: PathBuf, message: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
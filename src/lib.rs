@@ -5,7 +5,10 @@ pub mod package_managers;
 pub mod platform;
 
 pub use cli::{Cli, OutputFormat};
-pub use detector::detect_command;
+pub use detector::inventory::Inventory;
+pub use detector::{detect_all, detect_command, Detector, PathCopy};
 pub use error::{Result, WhyError};
-pub use package_managers::{Confidence, DetectionResult};
+pub use package_managers::{
+    Confidence, DetectionCandidate, DetectionResult, PackageInfo, Verification,
+};
 pub use platform::Platform;
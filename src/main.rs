@@ -1,6 +1,10 @@
 use clap::Parser;
-use colored::Colorize;
-use why::{detect_command, Cli, Confidence, DetectionResult, OutputFormat};
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use why::{
+    Cli, Confidence, DetectionCandidate, DetectionResult, Detector, Inventory, OutputFormat,
+    PathCopy,
+};
 
 fn main() {
     let cli = Cli::parse();
@@ -12,17 +16,141 @@ fn main() {
         cli.format
     };
 
-    match detect_command(&cli.command, cli.verbose) {
-        Ok(result) => {
-            print_result(&result, format);
+    let mut detector = Detector::new(cli.verbose)
+        .with_verify(!cli.no_verify)
+        .with_info(cli.info);
+
+    if let Some(ref database) = cli.database {
+        detector = detector
+            .with_database(database)
+            .unwrap_or_else(|e| exit_with_error(e));
+    }
+
+    if cli.inventory {
+        print_inventory(&detector.inventory(), format);
+        return;
+    }
+
+    let reports: Vec<CommandReport> = cli
+        .commands
+        .iter()
+        .map(|command| CommandReport {
+            command: command.clone(),
+            outcome: run(&detector, &cli, command).unwrap_or_else(|e| Outcome::Error {
+                error: e.to_string(),
+            }),
+        })
+        .collect();
+
+    // A single command keeps the plain (non-array) output format
+    if let [report] = reports.as_slice() {
+        match &report.outcome {
+            Outcome::Error { error } => exit_with_error(error),
+            outcome => print_outcome(&report.command, outcome, format),
         }
-        Err(e) => {
-            eprintln!("{}: {}", "Error".red().bold(), e);
-            std::process::exit(1);
+        return;
+    }
+
+    print_reports(&reports, format);
+    if reports
+        .iter()
+        .any(|r| matches!(r.outcome, Outcome::Error { .. }))
+    {
+        std::process::exit(1);
+    }
+}
+
+/// Detection outcome for one command on the command line.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Outcome {
+    Result(Box<DetectionResult>),
+    Matches { matches: Vec<DetectionCandidate> },
+    Copies { copies: Vec<PathCopy> },
+    Error { error: String },
+}
+
+#[derive(Serialize)]
+struct CommandReport {
+    command: String,
+    #[serde(flatten)]
+    outcome: Outcome,
+}
+
+fn run(detector: &Detector, cli: &Cli, command: &str) -> why::Result<Outcome> {
+    if cli.all {
+        return Ok(Outcome::Copies {
+            copies: detector.detect_all_copies(command)?,
+        });
+    }
+    if cli.all_matches {
+        return Ok(Outcome::Matches {
+            matches: detector.detect_all(command)?,
+        });
+    }
+    Ok(Outcome::Result(Box::new(detector.detect(command)?)))
+}
+
+fn print_outcome(command: &str, outcome: &Outcome, format: OutputFormat) {
+    match outcome {
+        Outcome::Result(result) => print_result(result, format),
+        Outcome::Matches { matches } => print_candidates(command, matches, format),
+        Outcome::Copies { copies } => print_copies(command, copies, format),
+        Outcome::Error { error } => eprintln!("{}: {}", "Error".red().bold(), error),
+    }
+}
+
+/// Combined report for several commands.
+fn print_reports(reports: &[CommandReport], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(reports).unwrap());
+        }
+        OutputFormat::Short => {
+            for report in reports {
+                match &report.outcome {
+                    Outcome::Result(result) => {
+                        println!("{}\t{}", report.command, result.manager_id)
+                    }
+                    Outcome::Matches { matches } => {
+                        let ids: Vec<&str> = matches
+                            .iter()
+                            .map(|c| c.result.manager_id.as_str())
+                            .collect();
+                        println!("{}\t{}", report.command, ids.join(","));
+                    }
+                    Outcome::Copies { copies } => {
+                        for copy in copies {
+                            println!(
+                                "{}\t{}\t{}",
+                                report.command,
+                                copy.result.command_path.display(),
+                                copy.result.manager_id
+                            );
+                        }
+                    }
+                    Outcome::Error { error } => {
+                        eprintln!("{}: {}", "Error".red().bold(), error)
+                    }
+                }
+            }
+        }
+        OutputFormat::Text => {
+            for (i, report) in reports.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_outcome(&report.command, &report.outcome, format);
+            }
         }
     }
 }
 
+fn exit_with_error(e: impl std::fmt::Display) -> ! {
+    eprintln!("{}: {}", "Error".red().bold(), e);
+    std::process::exit(1);
+}
+
 fn print_result(result: &DetectionResult, format: OutputFormat) {
     match format {
         OutputFormat::Json => {
@@ -37,13 +165,146 @@ fn print_result(result: &DetectionResult, format: OutputFormat) {
     }
 }
 
-fn print_text_result(result: &DetectionResult) {
-    let confidence_str = match result.confidence {
+fn print_candidates(command: &str, candidates: &[DetectionCandidate], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(candidates).unwrap());
+        }
+        OutputFormat::Short => {
+            for candidate in candidates {
+                println!("{}", candidate.result.manager_id);
+            }
+        }
+        OutputFormat::Text => {
+            if candidates.is_empty() {
+                println!("{} did not match any package manager", command.bold());
+                return;
+            }
+
+            println!(
+                "{} matched {} package manager(s):",
+                command.bold(),
+                candidates.len()
+            );
+            for (i, candidate) in candidates.iter().enumerate() {
+                let result = &candidate.result;
+                println!(
+                    "  {}. {} {}",
+                    i + 1,
+                    result.manager_name.cyan().bold(),
+                    confidence_label(result.confidence)
+                );
+                if let Some(ref package) = result.package_name {
+                    println!("     {}: {}", "Package".dimmed(), package);
+                }
+                if let Some(ref version) = result.version {
+                    println!("     {}: {}", "Version".dimmed(), version);
+                }
+                if let Some(ref matched) = candidate.matched_path {
+                    println!("     {}: {}", "Matched".dimmed(), matched.display());
+                }
+            }
+        }
+    }
+}
+
+fn print_copies(command: &str, copies: &[PathCopy], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(copies).unwrap());
+        }
+        OutputFormat::Short => {
+            for copy in copies {
+                println!(
+                    "{}\t{}",
+                    copy.result.command_path.display(),
+                    copy.result.manager_id
+                );
+            }
+        }
+        OutputFormat::Text => {
+            println!(
+                "{} has {} cop{} on PATH:",
+                command.bold(),
+                copies.len(),
+                if copies.len() == 1 { "y" } else { "ies" }
+            );
+            for (i, copy) in copies.iter().enumerate() {
+                let result = &copy.result;
+                let status = if copy.active {
+                    "(active)".green().bold()
+                } else {
+                    "(shadowed)".dimmed()
+                };
+                println!(
+                    "  {}. {} {}",
+                    i + 1,
+                    result.command_path.display().to_string().bold(),
+                    status
+                );
+                println!(
+                    "     {}: {} {}",
+                    "Installed by".dimmed(),
+                    result.manager_name.cyan().bold(),
+                    confidence_label(result.confidence)
+                );
+                if let Some(ref package) = result.package_name {
+                    println!("     {}: {}", "Package".dimmed(), package);
+                }
+                if let Some(ref version) = result.version {
+                    println!("     {}: {}", "Version".dimmed(), version);
+                }
+                if result.resolved_path != result.command_path {
+                    println!(
+                        "     {}: {}",
+                        "Location".dimmed(),
+                        result.resolved_path.display()
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn print_inventory(inventory: &Inventory, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(inventory).unwrap());
+        }
+        OutputFormat::Short => {
+            for group in &inventory.managers {
+                println!("{}\t{}", group.manager_id, group.count);
+            }
+            println!("unknown\t{}", inventory.unknown.len());
+        }
+        OutputFormat::Text => {
+            for group in &inventory.managers {
+                println!("{} ({})", group.manager_name.cyan().bold(), group.count);
+                let names: Vec<&str> = group.commands.iter().map(|e| e.command.as_str()).collect();
+                println!("  {}", names.join(", "));
+            }
+            if !inventory.unknown.is_empty() {
+                println!("{} ({})", "Unknown".red().bold(), inventory.unknown.len());
+                for entry in &inventory.unknown {
+                    println!("  {}", entry.path.display());
+                }
+            }
+            println!("{}: {} commands", "Total".bold(), inventory.total);
+        }
+    }
+}
+
+fn confidence_label(confidence: Confidence) -> ColoredString {
+    match confidence {
         Confidence::High => "(verified)".green(),
         Confidence::Medium => "(likely)".yellow(),
         Confidence::Low => "(possible)".yellow(),
         Confidence::Uncertain => "(uncertain)".red(),
-    };
+    }
+}
+
+fn print_text_result(result: &DetectionResult) {
+    let confidence_str = confidence_label(result.confidence);
 
     println!(
         "{} was installed by: {} {}",
@@ -65,9 +326,63 @@ fn print_text_result(result: &DetectionResult) {
         println!("  {}: {}", "Version".dimmed(), version);
     }
 
+    for (key, value) in &result.details {
+        println!("  {}: {}", detail_label(key).dimmed(), value);
+    }
+
     println!(
         "  {}: {}",
         "Location".dimmed(),
         result.resolved_path.display()
     );
+
+    if let Some(ref info) = result.info {
+        if let Some(ref description) = info.description {
+            println!("  {}: {}", "Description".dimmed(), description);
+        }
+        if let Some(ref homepage) = info.homepage {
+            println!("  {}: {}", "Homepage".dimmed(), homepage);
+        }
+        if let Some(ref license) = info.license {
+            println!("  {}: {}", "License".dimmed(), license);
+        }
+        if let Some(ref install_date) = info.install_date {
+            println!("  {}: {}", "Installed".dimmed(), install_date);
+        }
+        if let Some(on_request) = info.installed_on_request {
+            let reason = if on_request {
+                "on request"
+            } else {
+                "as a dependency"
+            };
+            println!("  {}: {}", "Installed as".dimmed(), reason);
+        }
+        if let Some(ref source) = info.source {
+            println!("  {}: {}", "Source".dimmed(), source);
+        }
+        if !info.provides.is_empty() {
+            println!("  {}: {}", "Provides".dimmed(), info.provides.join(", "));
+        }
+        for (key, value) in &info.extra {
+            if value.contains('\n') {
+                // Multi-line details (a dependency tree) go below the label
+                println!("  {}:", detail_label(key).dimmed());
+                for line in value.lines() {
+                    println!("    {}", line);
+                }
+            } else {
+                println!("  {}: {}", detail_label(key).dimmed(), value);
+            }
+        }
+    }
+}
+
+/// Turn a snake_case detail key into a label (`python_version` -> `Python version`).
+fn detail_label(key: &str) -> String {
+    let label = key.replace('_', " ");
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => label,
+    }
 }
@@ -0,0 +1,474 @@
+//! Shim resolution for the rbenv family of version managers (pyenv, rbenv,
+//! nodenv, goenv, jenv). They all share one layout:
+//!
+//! - `<root>/shims/<command>`: generic script that dispatches to a version
+//! - `<root>/versions/<version>/bin/<command>`: the real binary
+//! - `<root>/version`: the global version
+//!
+//! The selected version is worked out from files and the environment, the same
+//! way the tool does, without executing the shim.
+
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use std::path::{Path, PathBuf};
+
+/// Static description of one tool in the family.
+pub(super) struct EnvTool {
+    id: &'static str,
+    /// Runtime the tool manages, reported as the package name
+    runtime: &'static str,
+    /// Environment variable overriding the root directory
+    root_var: &'static str,
+    /// Root directory relative to the home directory
+    default_root: &'static str,
+    /// Environment variable selecting the version for the current shell
+    version_var: &'static str,
+    /// Per-project version file looked up from the current directory upwards
+    version_file: &'static str,
+    /// Prefixes some version files carry (`ruby-3.2.2`, `v20.11.0`)
+    version_prefixes: &'static [&'static str],
+}
+
+pub(super) const PYENV: EnvTool = EnvTool {
+    id: "pyenv",
+    runtime: "python",
+    root_var: "PYENV_ROOT",
+    default_root: ".pyenv",
+    version_var: "PYENV_VERSION",
+    version_file: ".python-version",
+    version_prefixes: &["python-"],
+};
+
+pub(super) const RBENV: EnvTool = EnvTool {
+    id: "rbenv",
+    runtime: "ruby",
+    root_var: "RBENV_ROOT",
+    default_root: ".rbenv",
+    version_var: "RBENV_VERSION",
+    version_file: ".ruby-version",
+    version_prefixes: &["ruby-"],
+};
+
+pub(super) const NODENV: EnvTool = EnvTool {
+    id: "nodenv",
+    runtime: "node",
+    root_var: "NODENV_ROOT",
+    default_root: ".nodenv",
+    version_var: "NODENV_VERSION",
+    version_file: ".node-version",
+    version_prefixes: &["v", "node-"],
+};
+
+pub(super) const GOENV: EnvTool = EnvTool {
+    id: "goenv",
+    runtime: "go",
+    root_var: "GOENV_ROOT",
+    default_root: ".goenv",
+    version_var: "GOENV_VERSION",
+    version_file: ".go-version",
+    version_prefixes: &["go"],
+};
+
+pub(super) const JENV: EnvTool = EnvTool {
+    id: "jenv",
+    runtime: "java",
+    root_var: "JENV_ROOT",
+    default_root: ".jenv",
+    version_var: "JENV_VERSION",
+    version_file: ".java-version",
+    version_prefixes: &[],
+};
+
+/// Detector for one rbenv-style version manager.
+pub struct AnyenvDetector {
+    tool: &'static EnvTool,
+    root: Option<PathBuf>,
+    /// Value of the tool's `*_VERSION` variable
+    version_override: Option<String>,
+    cwd: Option<PathBuf>,
+}
+
+impl AnyenvDetector {
+    pub fn new(tool: &'static EnvTool) -> Self {
+        let root = std::env::var_os(tool.root_var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(tool.default_root)));
+        Self {
+            tool,
+            root,
+            version_override: std::env::var(tool.version_var)
+                .ok()
+                .filter(|v| !v.is_empty()),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_environment(
+        tool: &'static EnvTool,
+        root: &Path,
+        version_override: Option<&str>,
+        cwd: &Path,
+    ) -> Self {
+        Self {
+            tool,
+            root: Some(root.to_path_buf()),
+            version_override: version_override.map(str::to_string),
+            cwd: Some(cwd.to_path_buf()),
+        }
+    }
+
+    /// The versions selected for the current directory, and what selected them.
+    fn selected_versions(&self, root: &Path) -> Option<(Vec<String>, String)> {
+        if let Some(ref value) = self.version_override {
+            return Some((parse_versions(value), self.tool.version_var.to_string()));
+        }
+
+        if let Some(ref cwd) = self.cwd {
+            for dir in cwd.ancestors() {
+                let file = dir.join(self.tool.version_file);
+                if let Some(versions) = read_version_file(&file) {
+                    return Some((versions, file.display().to_string()));
+                }
+            }
+        }
+
+        let global = root.join("version");
+        read_version_file(&global).map(|versions| (versions, global.display().to_string()))
+    }
+
+    /// Map a selected version to an installed `versions/<v>` directory name.
+    fn installed_version(&self, root: &Path, version: &str) -> Option<String> {
+        let candidates = std::iter::once(version).chain(
+            self.tool
+                .version_prefixes
+                .iter()
+                .filter_map(|prefix| version.strip_prefix(prefix)),
+        );
+        candidates
+            .map(str::to_string)
+            .find(|v| root.join("versions").join(v).is_dir())
+    }
+
+    /// Resolve a shim to `(version, real binary, selected by)`.
+    /// `system` versions have no binary under the root.
+    fn resolve_shim(
+        &self,
+        root: &Path,
+        command: &str,
+    ) -> Option<(String, Option<PathBuf>, String)> {
+        let (versions, origin) = self.selected_versions(root)?;
+
+        // pyenv allows several versions; the first one providing the command wins
+        for version in &versions {
+            if version == "system" {
+                return Some((version.clone(), None, origin));
+            }
+            if let Some(installed) = self.installed_version(root, version) {
+                let binary = root
+                    .join("versions")
+                    .join(&installed)
+                    .join("bin")
+                    .join(command);
+                if binary.exists() {
+                    return Some((installed, Some(binary), origin));
+                }
+            }
+        }
+        Some((versions.first()?.clone(), None, origin))
+    }
+
+    fn result(
+        &self,
+        ctx: &DetectionContext,
+        version: Option<String>,
+        resolved_path: PathBuf,
+        confidence: Confidence,
+    ) -> DetectionResult {
+        DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(self.tool.runtime.to_string()),
+            version,
+            details: Default::default(),
+            confidence,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path,
+        }
+    }
+}
+
+/// Versions listed in a version file (whitespace separated, `#` comments).
+fn read_version_file(path: &Path) -> Option<Vec<String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let versions = parse_versions(&content);
+    (!versions.is_empty()).then_some(versions)
+}
+
+fn parse_versions(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ':'))
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl PackageManagerDetector for AnyenvDetector {
+    fn id(&self) -> &'static str {
+        self.tool.id
+    }
+
+    fn name(&self) -> &str {
+        self.tool.id
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        matches!(platform, Platform::MacOS | Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        90 // Same as mise, since these also use shims
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let root = self.root.as_ref()?;
+        let shims = root.join("shims");
+        let versions = root.join("versions");
+
+        // Medium until verification finds the selected version's binary
+        for path in &ctx.symlink_chain {
+            if path.parent() == Some(shims.as_path()) {
+                let version = self
+                    .selected_versions(root)
+                    .and_then(|(versions, _)| versions.into_iter().next());
+                return Some(self.result(
+                    ctx,
+                    version,
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ));
+            }
+
+            // Called directly as versions/<v>/bin/<command>
+            if let Ok(relative) = path.strip_prefix(&versions) {
+                let version = relative.iter().next()?.to_string_lossy().to_string();
+                return Some(self.result(
+                    ctx,
+                    Some(version),
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ));
+            }
+        }
+
+        None
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(root) = self.root.as_ref() else {
+            return Verification::Unverified;
+        };
+        let shims = root.join("shims");
+        let versions = root.join("versions");
+
+        for path in &ctx.symlink_chain {
+            if path.parent() == Some(shims.as_path()) {
+                let Some(command) = path.file_name() else {
+                    break;
+                };
+                return match self.resolve_shim(root, &command.to_string_lossy()) {
+                    Some((version, Some(binary), _)) => {
+                        result.version = Some(version);
+                        result.resolved_path = binary;
+                        Verification::Verified
+                    }
+                    _ => Verification::Unverified,
+                };
+            }
+
+            if let Ok(relative) = path.strip_prefix(&versions) {
+                let installed = relative
+                    .iter()
+                    .next()
+                    .is_some_and(|version| versions.join(version).is_dir());
+                return if installed && path.exists() {
+                    Verification::Verified
+                } else {
+                    Verification::Unverified
+                };
+            }
+        }
+
+        Verification::Unverified
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let root = self.root.as_ref()?;
+        let version = result.version.as_ref()?;
+        let version_dir = root.join("versions").join(version);
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&version_dir),
+            installed_on_request: Some(true),
+            provides: super::info::list_commands(&version_dir.join("bin")),
+            ..Default::default()
+        };
+        let is_shim = ctx
+            .symlink_chain
+            .iter()
+            .any(|p| p.parent() == Some(root.join("shims").as_path()));
+        if is_shim {
+            if let Some((_, origin)) = self.selected_versions(root) {
+                info.extra.insert("selected_by".to_string(), origin);
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.to_path_buf(),
+            symlink_chain: vec![path.to_path_buf()],
+            resolved_path: path.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    /// A pyenv root with 3.11.7 and 3.12.1 installed and 3.11.7 as the global version.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join(".pyenv");
+        std::fs::create_dir_all(root.join("shims")).unwrap();
+        std::fs::write(root.join("shims/python"), "#!/usr/bin/env bash\n").unwrap();
+        for version in ["3.11.7", "3.12.1"] {
+            let bin = root.join("versions").join(version).join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            std::fs::write(bin.join("python"), "").unwrap();
+        }
+        std::fs::write(root.join("version"), "3.11.7\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("project/src")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_global_version() {
+        let home = fixture();
+        let root = home.path().join(".pyenv");
+        let detector = AnyenvDetector::with_environment(&PYENV, &root, None, home.path());
+        let ctx = make_context("python", &root.join("shims/python"));
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "pyenv");
+        assert_eq!(result.package_name, Some("python".to_string()));
+        assert_eq!(result.version, Some("3.11.7".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(
+            result.resolved_path,
+            root.join("versions/3.11.7/bin/python")
+        );
+    }
+
+    #[test]
+    fn test_local_version_file_walks_up() {
+        let home = fixture();
+        let root = home.path().join(".pyenv");
+        std::fs::write(home.path().join("project/.python-version"), "3.12.1\n").unwrap();
+        let cwd = home.path().join("project/src");
+        let detector = AnyenvDetector::with_environment(&PYENV, &root, None, &cwd);
+        let ctx = make_context("python", &root.join("shims/python"));
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("3.12.1".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert!(info.extra["selected_by"].ends_with(".python-version"));
+    }
+
+    #[test]
+    fn test_environment_variable_wins() {
+        let home = fixture();
+        let root = home.path().join(".pyenv");
+        std::fs::write(home.path().join("project/.python-version"), "3.11.7\n").unwrap();
+        let cwd = home.path().join("project");
+        let detector = AnyenvDetector::with_environment(&PYENV, &root, Some("3.12.1"), &cwd);
+        let result = detector
+            .detect(&make_context("python", &root.join("shims/python")))
+            .unwrap();
+        assert_eq!(result.version, Some("3.12.1".to_string()));
+    }
+
+    #[test]
+    fn test_system_version() {
+        let home = fixture();
+        let root = home.path().join(".pyenv");
+        let detector = AnyenvDetector::with_environment(&PYENV, &root, Some("system"), home.path());
+        let ctx = make_context("python", &root.join("shims/python"));
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("system".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_version_prefix() {
+        let home = tempfile::tempdir().unwrap();
+        let root = home.path().join(".nodenv");
+        std::fs::create_dir_all(root.join("versions/20.11.0/bin")).unwrap();
+        std::fs::write(root.join("versions/20.11.0/bin/node"), "").unwrap();
+        std::fs::write(home.path().join(".node-version"), "v20.11.0\n").unwrap();
+        let detector = AnyenvDetector::with_environment(&NODENV, &root, None, home.path());
+        let ctx = make_context("node", &root.join("shims/node"));
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "nodenv");
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+    }
+
+    #[test]
+    fn test_direct_version_path() {
+        let home = fixture();
+        let root = home.path().join(".pyenv");
+        let detector = AnyenvDetector::with_environment(&PYENV, &root, None, home.path());
+        let ctx = make_context("python", &root.join("versions/3.12.1/bin/python"));
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("3.12.1".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let home = fixture();
+        let root = home.path().join(".rbenv");
+        let detector = AnyenvDetector::with_environment(&RBENV, &root, None, home.path());
+        assert!(detector
+            .detect(&make_context(
+                "python",
+                &home.path().join(".pyenv/shims/python")
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(
+            parse_versions("3.11.7 3.10.13 # comment\n"),
+            vec!["3.11.7", "3.10.13"]
+        );
+        assert_eq!(parse_versions("3.12.1:3.11.7"), vec!["3.12.1", "3.11.7"]);
+    }
+}
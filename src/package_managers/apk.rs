@@ -0,0 +1,338 @@
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Detector for Alpine Linux apk packages.
+/// Reads the installed database (`/lib/apk/db/installed`), so the `apk`
+/// binary itself is not needed.
+pub struct ApkDetector {
+    root: PathBuf,
+    database: OnceCell<ApkDatabase>,
+}
+
+impl ApkDetector {
+    pub fn new() -> Self {
+        Self::with_root(Path::new("/"))
+    }
+
+    /// Use the apk database below `root` instead of the running system's.
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            database: OnceCell::new(),
+        }
+    }
+
+    fn installed_path(&self) -> PathBuf {
+        self.root.join("lib/apk/db/installed")
+    }
+
+    fn database(&self) -> &ApkDatabase {
+        self.database.get_or_init(|| {
+            std::fs::read_to_string(self.installed_path())
+                .map(|content| ApkDatabase::parse(&content))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Find the package that lists `path`, also trying the `/bin` <-> `/usr/bin`
+    /// counterpart since newer Alpine releases are moving to a merged `/usr`.
+    fn find_owner(&self, path: &Path) -> Option<&ApkPackage> {
+        let database = self.database();
+        let path_str = path.to_string_lossy();
+        let alias = match path_str.strip_prefix("/usr") {
+            Some(rest) if rest.starts_with("/bin/") || rest.starts_with("/sbin/") => {
+                Some(PathBuf::from(rest))
+            }
+            _ if path_str.starts_with("/bin/") || path_str.starts_with("/sbin/") => {
+                Some(Path::new("/usr").join(path.strip_prefix("/").ok()?))
+            }
+            _ => None,
+        };
+
+        database
+            .owner_of(path)
+            .or_else(|| alias.and_then(|alias| database.owner_of(&alias)))
+    }
+}
+
+/// A record from `/lib/apk/db/installed`.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ApkPackage {
+    /// `P:`
+    name: String,
+    /// `V:`
+    version: String,
+    /// `T:`
+    description: Option<String>,
+    /// `U:`
+    url: Option<String>,
+    /// `L:`
+    license: Option<String>,
+    /// Absolute paths built from `F:` (directory) and `R:` (file) lines
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct ApkDatabase {
+    packages: Vec<ApkPackage>,
+    /// File path -> index into `packages`
+    files: HashMap<PathBuf, usize>,
+}
+
+impl ApkDatabase {
+    fn parse(content: &str) -> Self {
+        let packages: Vec<ApkPackage> = content.split("\n\n").filter_map(parse_record).collect();
+
+        let mut files = HashMap::new();
+        for (i, package) in packages.iter().enumerate() {
+            for file in &package.files {
+                files.insert(file.clone(), i);
+            }
+        }
+
+        Self { packages, files }
+    }
+
+    fn owner_of(&self, path: &Path) -> Option<&ApkPackage> {
+        self.files.get(path).map(|&i| &self.packages[i])
+    }
+}
+
+/// Parse one blank-line separated package record.
+fn parse_record(record: &str) -> Option<ApkPackage> {
+    let mut package = ApkPackage::default();
+    let mut dir = PathBuf::from("/");
+
+    for line in record.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key {
+            "P" => package.name = value.to_string(),
+            "V" => package.version = value.to_string(),
+            "T" => package.description = Some(value.to_string()),
+            "U" => package.url = Some(value.to_string()),
+            "L" => package.license = Some(value.to_string()),
+            "F" => dir = Path::new("/").join(value),
+            "R" => package.files.push(dir.join(value)),
+            _ => {}
+        }
+    }
+
+    (!package.name.is_empty()).then_some(package)
+}
+
+/// Whether `package` is listed in `/etc/apk/world` (i.e. explicitly installed).
+/// World entries may carry constraints such as `git>2.40` or `foo@testing`.
+fn in_world(world: &str, package: &str) -> bool {
+    world.split_whitespace().any(|entry| {
+        let name = entry
+            .split(['<', '>', '=', '~', '@'])
+            .next()
+            .unwrap_or(entry);
+        name == package
+    })
+}
+
+impl PackageManagerDetector for ApkDetector {
+    fn id(&self) -> &'static str {
+        "apk"
+    }
+
+    fn name(&self) -> &str {
+        "apk"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        matches!(platform, Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        50 // Same level as apt
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        if !self.installed_path().is_file() {
+            return None;
+        }
+
+        let package = self.find_owner(&ctx.resolved_path)?;
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(package.name.clone()),
+            version: Some(package.version.clone()),
+            details: Default::default(),
+            confidence: Confidence::High,
+            // The installed database itself records ownership, so no separate verification is needed
+            verification: Verification::Verified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let package = self.find_owner(&ctx.resolved_path)?;
+
+        let mut provides: Vec<String> = package
+            .files
+            .iter()
+            .filter(|p| {
+                p.parent()
+                    .and_then(|d| d.file_name())
+                    .is_some_and(|d| d == "bin" || d == "sbin")
+            })
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect();
+        provides.sort();
+        provides.dedup();
+
+        Some(PackageInfo {
+            description: package.description.clone(),
+            homepage: package.url.clone(),
+            license: package.license.clone(),
+            installed_on_request: std::fs::read_to_string(self.root.join("etc/apk/world"))
+                .ok()
+                .map(|world| in_world(&world, &package.name)),
+            provides,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &str) -> DetectionContext {
+        let path = PathBuf::from(path);
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.clone(),
+            symlink_chain: vec![path.clone()],
+            resolved_path: path,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    const INSTALLED: &str = "\
+C:Q1abc=
+P:busybox
+V:1.36.1-r15
+A:x86_64
+T:Size optimized toolbox of many common UNIX utilities
+U:https://busybox.net/
+L:GPL-2.0-only
+F:bin
+R:busybox
+R:sh
+F:etc
+R:securetty
+
+C:Q1def=
+P:git
+V:2.43.0-r0
+A:x86_64
+T:Distributed version control system
+U:https://www.git-scm.com/
+L:GPL-2.0-or-later
+F:usr/bin
+R:git
+R:git-shell
+F:usr/libexec/git-core
+R:git-add
+";
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lib/apk/db")).unwrap();
+        std::fs::create_dir_all(dir.path().join("etc/apk")).unwrap();
+        std::fs::write(dir.path().join("lib/apk/db/installed"), INSTALLED).unwrap();
+        std::fs::write(dir.path().join("etc/apk/world"), "alpine-base\ngit>2.40\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let database = ApkDatabase::parse(INSTALLED);
+        assert_eq!(database.packages.len(), 2);
+        let busybox = database.owner_of(Path::new("/bin/sh")).unwrap();
+        assert_eq!(busybox.name, "busybox");
+        assert_eq!(busybox.version, "1.36.1-r15");
+        assert!(database.owner_of(Path::new("/etc/securetty")).is_some());
+        assert!(database.owner_of(Path::new("/bin/securetty")).is_none());
+    }
+
+    #[test]
+    fn test_detects_listed_file() {
+        let root = fixture();
+        let detector = ApkDetector::with_root(root.path());
+        let result = detector
+            .detect(&make_context("git", "/usr/bin/git"))
+            .unwrap();
+        assert_eq!(result.manager_id, "apk");
+        assert_eq!(result.package_name, Some("git".to_string()));
+        assert_eq!(result.version, Some("2.43.0-r0".to_string()));
+        assert_eq!(result.confidence, Confidence::High);
+    }
+
+    #[test]
+    fn test_usrmerge_alias() {
+        let root = fixture();
+        let detector = ApkDetector::with_root(root.path());
+        let result = detector
+            .detect(&make_context("busybox", "/usr/bin/busybox"))
+            .unwrap();
+        assert_eq!(result.package_name, Some("busybox".to_string()));
+    }
+
+    #[test]
+    fn test_unlisted_file() {
+        let root = fixture();
+        let detector = ApkDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("custom", "/usr/local/bin/custom"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_missing_database() {
+        let root = tempfile::tempdir().unwrap();
+        let detector = ApkDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("git", "/usr/bin/git"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_info() {
+        let root = fixture();
+        let detector = ApkDetector::with_root(root.path());
+        let ctx = make_context("git", "/usr/bin/git");
+        let result = detector.detect(&ctx).unwrap();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Distributed version control system")
+        );
+        assert_eq!(info.license.as_deref(), Some("GPL-2.0-or-later"));
+        assert_eq!(info.installed_on_request, Some(true));
+        assert_eq!(info.provides, vec!["git", "git-shell"]);
+    }
+
+    #[test]
+    fn test_in_world() {
+        let world = "alpine-base\ngit>2.40\nfoo@testing\n";
+        assert!(in_world(world, "git"));
+        assert!(in_world(world, "foo"));
+        assert!(!in_world(world, "busybox"));
+    }
+}
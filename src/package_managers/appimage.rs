@@ -0,0 +1,454 @@
+use super::binary::{elf_size, is_elf, read_at, read_elf_section};
+use super::squashfs::read_root_file;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Type 1 AppImages (ISO 9660) keep the update information at a fixed offset.
+const TYPE1_UPDATE_INFO_OFFSET: u64 = 33651;
+const TYPE1_UPDATE_INFO_LEN: usize = 512;
+
+/// Directories watched by appimaged (relative to the home directory, or absolute).
+const APPIMAGED_DIRS: &[&str] = &[
+    "Applications",
+    "Downloads",
+    ".local/bin",
+    "bin",
+    "/Applications",
+    "/opt",
+    "/usr/local/bin",
+];
+
+/// `{Name}-{version}[-{arch}].AppImage`
+static FILENAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?P<name>.+?)(?:[-_]v?(?P<version>\d[\w.+~]*?))?(?:[-_](?:x86_64|amd64|aarch64|arm64|armhf|i386|i686))?\.appimage$").unwrap()
+});
+
+/// Detector for AppImages, identified by the magic bytes in the ELF header.
+pub struct AppImageDetector {
+    home: Option<PathBuf>,
+}
+
+impl AppImageDetector {
+    pub fn new() -> Self {
+        Self {
+            home: dirs::home_dir(),
+        }
+    }
+
+    /// Use `home` instead of the user's home directory (for integration lookups).
+    #[cfg(test)]
+    fn with_home(home: &Path) -> Self {
+        Self {
+            home: Some(home.to_path_buf()),
+        }
+    }
+
+    /// The integration tool managing `path`, if it is in one of its directories.
+    fn manager(&self, path: &Path) -> Option<&'static str> {
+        let home = self.home.as_ref()?;
+        let dir = path.parent()?;
+
+        let launcher_config = home.join(".config/appimagelauncher.cfg");
+        if launcher_config.is_file() {
+            let destination = std::fs::read_to_string(&launcher_config)
+                .ok()
+                .and_then(|content| launcher_destination(&content, home))
+                .unwrap_or_else(|| home.join("Applications"));
+            if dir == destination {
+                return Some("AppImageLauncher");
+            }
+        }
+
+        if home
+            .join(".config/systemd/user/appimaged.service")
+            .is_file()
+            && APPIMAGED_DIRS.iter().any(|d| dir == home.join(d))
+        {
+            return Some("appimaged");
+        }
+        None
+    }
+
+    /// Desktop entry written by AppImageLauncher/appimaged when integrating
+    /// `path`, or else the one inside the image.
+    fn desktop_entry(&self, path: &Path, appimage_type: u8) -> Option<DesktopEntry> {
+        let integrated = self
+            .home
+            .as_ref()
+            .map(|home| home.join(".local/share/applications"))
+            .and_then(|applications| find_desktop_entry(&applications, path));
+        integrated.or_else(|| embedded_desktop_entry(path, appimage_type))
+    }
+}
+
+/// Read `destination = ...` from `appimagelauncher.cfg`.
+fn launcher_destination(content: &str, home: &Path) -> Option<PathBuf> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != "destination" {
+            return None;
+        }
+        let value = value.trim();
+        Some(match value.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(value),
+        })
+    })
+}
+
+/// AppImage type (1 or 2) from the magic bytes at offset 8 of the ELF header.
+fn appimage_type(path: &Path) -> Option<u8> {
+    let mut file = File::open(path).ok()?;
+    let header = read_at(&mut file, 0, 11)?;
+    if !is_elf(&header) || &header[8..10] != b"AI" {
+        return None;
+    }
+    matches!(header[10], 1 | 2).then_some(header[10])
+}
+
+/// The embedded update information string (e.g. `gh-releases-zsync|...`).
+fn update_information(path: &Path, appimage_type: u8) -> Option<String> {
+    let raw = match appimage_type {
+        1 => read_at(
+            &mut File::open(path).ok()?,
+            TYPE1_UPDATE_INFO_OFFSET,
+            TYPE1_UPDATE_INFO_LEN,
+        )?,
+        _ => read_elf_section(path, ".upd_info")?,
+    };
+    let text = String::from_utf8_lossy(raw.split(|&b| b == 0).next()?)
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The `.desktop` file every AppImage carries in its root directory. Only
+/// type 2 images (SquashFS appended to the runtime) are read.
+fn embedded_desktop_entry(path: &Path, appimage_type: u8) -> Option<DesktopEntry> {
+    if appimage_type != 2 {
+        return None;
+    }
+    let content = read_root_file(path, elf_size(path)?, |name| name.ends_with(".desktop"))?;
+    Some(parse_desktop_entry(&String::from_utf8_lossy(&content)))
+}
+
+/// Fields of an AppImage's `.desktop` file.
+#[derive(Debug, Default, PartialEq)]
+struct DesktopEntry {
+    name: Option<String>,
+    version: Option<String>,
+    comment: Option<String>,
+}
+
+/// Find the `.desktop` file whose `Exec=` runs `appimage`.
+fn find_desktop_entry(applications: &Path, appimage: &Path) -> Option<DesktopEntry> {
+    let target = appimage.to_string_lossy();
+    std::fs::read_dir(applications)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "desktop"))
+        .find_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            let runs_target = content.lines().any(|line| {
+                line.strip_prefix("Exec=").is_some_and(|exec| {
+                    let program = exec.split_whitespace().next().unwrap_or("");
+                    program.trim_matches('"') == target
+                })
+            });
+            runs_target.then(|| parse_desktop_entry(&content))
+        })
+}
+
+fn parse_desktop_entry(content: &str) -> DesktopEntry {
+    let mut entry = DesktopEntry::default();
+    let mut in_main = false;
+    for line in content.lines() {
+        if line.starts_with('[') {
+            in_main = line.trim() == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "Name" => entry.name = value,
+                "X-AppImage-Name" => entry.name = entry.name.or(value),
+                "X-AppImage-Version" => entry.version = value,
+                "Comment" => entry.comment = value,
+                _ => {}
+            }
+        }
+    }
+    entry
+}
+
+/// Split `Name-1.2.3-x86_64.AppImage` into name and version.
+fn parse_filename(file_name: &str) -> Option<(String, Option<String>)> {
+    let captures = FILENAME_REGEX.captures(file_name)?;
+    Some((
+        captures["name"].to_string(),
+        captures.name("version").map(|m| m.as_str().to_string()),
+    ))
+}
+
+impl PackageManagerDetector for AppImageDetector {
+    fn id(&self) -> &'static str {
+        "appimage"
+    }
+
+    fn name(&self) -> &str {
+        "AppImage"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        platform == Platform::Linux
+    }
+
+    fn priority(&self) -> i32 {
+        90 // The magic bytes are definitive, wherever the file lives
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        appimage_type(&ctx.resolved_path)?;
+
+        let manager_name = match self.manager(&ctx.resolved_path) {
+            Some(tool) => format!("AppImage ({})", tool),
+            None => self.name().to_string(),
+        };
+
+        // The file name is only a guess until verification reads the desktop entry
+        let file_name = ctx.resolved_path.file_name()?.to_string_lossy().to_string();
+        let (package_name, version) =
+            parse_filename(&file_name).unwrap_or((ctx.command_name.clone(), None));
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name,
+            package_name: Some(package_name),
+            version,
+            details: [("guessed_from".to_string(), "file name".to_string())].into(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let entry = appimage_type(&ctx.resolved_path)
+            .and_then(|appimage_type| self.desktop_entry(&ctx.resolved_path, appimage_type));
+        let Some(DesktopEntry {
+            name: Some(name),
+            version,
+            ..
+        }) = entry
+        else {
+            return Verification::Unverified;
+        };
+        result.package_name = Some(name);
+        result.version = version;
+        result.details.remove("guessed_from");
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let appimage_type = appimage_type(&ctx.resolved_path)?;
+        let entry = self
+            .desktop_entry(&ctx.resolved_path, appimage_type)
+            .unwrap_or_default();
+
+        let mut info = PackageInfo {
+            description: entry.comment,
+            installed_on_request: Some(true),
+            ..Default::default()
+        };
+        info.extra
+            .insert("appimage_type".to_string(), appimage_type.to_string());
+        if let Some(update) = update_information(&ctx.resolved_path, appimage_type) {
+            info.extra.insert("update_information".to_string(), update);
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::binary::tests::build_elf;
+    use super::super::squashfs::tests::build_squashfs;
+    use super::*;
+
+    fn make_context(command: &str, path: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.to_path_buf(),
+            symlink_chain: vec![path.to_path_buf()],
+            resolved_path: path.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    const UPDATE_INFO: &str =
+        "gh-releases-zsync|obsidianmd|obsidian-releases|latest|Obsidian-*.AppImage.zsync";
+
+    fn write_appimage(dir: &Path, name: &str) -> PathBuf {
+        let mut update = UPDATE_INFO.as_bytes().to_vec();
+        update.resize(512, 0);
+        let path = dir.join(name);
+        std::fs::write(&path, build_elf(&[(".upd_info", &update)], b"AI\x02")).unwrap();
+        path
+    }
+
+    /// A type 2 AppImage whose filesystem holds `desktop` as `obsidian.desktop`.
+    fn write_appimage_with_desktop(dir: &Path, name: &str, desktop: &str) -> PathBuf {
+        let path = write_appimage(dir, name);
+        let mut data = std::fs::read(&path).unwrap();
+        data.extend(build_squashfs(&[
+            ("AppRun", b"#!/bin/sh\n"),
+            ("obsidian.desktop", desktop.as_bytes()),
+        ]));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_filename() {
+        assert_eq!(
+            parse_filename("Obsidian-1.5.3.AppImage"),
+            Some(("Obsidian".to_string(), Some("1.5.3".to_string())))
+        );
+        assert_eq!(
+            parse_filename("nvim-x86_64.appimage"),
+            Some(("nvim".to_string(), None))
+        );
+        assert_eq!(
+            parse_filename("Krita_5.2.2-x86_64.AppImage"),
+            Some(("Krita".to_string(), Some("5.2.2".to_string())))
+        );
+        assert_eq!(parse_filename("krita"), None);
+    }
+
+    #[test]
+    fn test_detects_type2_appimage() {
+        let home = tempfile::tempdir().unwrap();
+        let path = write_appimage(home.path(), "Obsidian-1.5.3.AppImage");
+        let detector = AppImageDetector::with_home(home.path());
+        let ctx = make_context("obsidian", &path);
+
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "appimage");
+        assert_eq!(result.manager_name, "AppImage");
+        assert_eq!(result.package_name, Some("Obsidian".to_string()));
+        assert_eq!(result.version, Some("1.5.3".to_string()));
+        assert_eq!(result.details["guessed_from"], "file name");
+        assert_eq!(result.confidence, Confidence::Medium);
+        // No desktop entry to confirm the name and version
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+        assert_eq!(result.details["guessed_from"], "file name");
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.extra["appimage_type"], "2");
+        assert_eq!(info.extra["update_information"], UPDATE_INFO);
+    }
+
+    #[test]
+    fn test_reads_embedded_desktop_entry() {
+        let home = tempfile::tempdir().unwrap();
+        // Not integrated, and the file name carries no version
+        let path = write_appimage_with_desktop(
+            home.path(),
+            "obsidian.AppImage",
+            "[Desktop Entry]\nName=Obsidian\nComment=Knowledge base\nExec=obsidian %U\n\
+             X-AppImage-Version=1.5.3\n",
+        );
+        let detector = AppImageDetector::with_home(home.path());
+        let ctx = make_context("obsidian", &path);
+
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "AppImage");
+        assert_eq!(result.package_name, Some("obsidian".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("Obsidian".to_string()));
+        assert_eq!(result.version, Some("1.5.3".to_string()));
+        assert!(result.details.is_empty());
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.description.as_deref(), Some("Knowledge base"));
+        assert_eq!(info.extra["update_information"], UPDATE_INFO);
+    }
+
+    #[test]
+    fn test_ignores_plain_elf() {
+        let home = tempfile::tempdir().unwrap();
+        let path = home.path().join("tool");
+        std::fs::write(&path, build_elf(&[(".text", b"\x90")], &[])).unwrap();
+        let detector = AppImageDetector::with_home(home.path());
+        assert!(detector.detect(&make_context("tool", &path)).is_none());
+    }
+
+    #[test]
+    fn test_appimagelauncher_integration() {
+        let home = tempfile::tempdir().unwrap();
+        let apps = home.path().join("Applications");
+        std::fs::create_dir_all(&apps).unwrap();
+        std::fs::create_dir_all(home.path().join(".config")).unwrap();
+        std::fs::write(
+            home.path().join(".config/appimagelauncher.cfg"),
+            "[AppImageLauncher]\nask_to_move = true\n",
+        )
+        .unwrap();
+        let path = write_appimage(&apps, "obsidian_a1b2c3.AppImage");
+
+        let desktop_dir = home.path().join(".local/share/applications");
+        std::fs::create_dir_all(&desktop_dir).unwrap();
+        std::fs::write(
+            desktop_dir.join("appimagekit_a1b2c3-Obsidian.desktop"),
+            format!(
+                "[Desktop Entry]\nName=Obsidian\nComment=Knowledge base\nExec=\"{}\" %U\nX-AppImage-Version=1.5.3\n",
+                path.display()
+            ),
+        )
+        .unwrap();
+
+        let detector = AppImageDetector::with_home(home.path());
+        let ctx = make_context("obsidian", &path);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "AppImage (AppImageLauncher)");
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("Obsidian".to_string()));
+        assert_eq!(result.version, Some("1.5.3".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.description.as_deref(), Some("Knowledge base"));
+    }
+
+    #[test]
+    fn test_appimaged_directories() {
+        let home = tempfile::tempdir().unwrap();
+        let bin = home.path().join(".local/bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::create_dir_all(home.path().join(".config/systemd/user")).unwrap();
+        std::fs::write(
+            home.path().join(".config/systemd/user/appimaged.service"),
+            "",
+        )
+        .unwrap();
+        let path = write_appimage(&bin, "Obsidian-1.5.3.AppImage");
+
+        let detector = AppImageDetector::with_home(home.path());
+        let result = detector.detect(&make_context("obsidian", &path)).unwrap();
+        assert_eq!(result.manager_name, "AppImage (appimaged)");
+    }
+}
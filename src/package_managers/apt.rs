@@ -1,13 +1,30 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::dpkg::DpkgDatabase;
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 
 /// Detector for apt/dpkg packages (Debian/Ubuntu).
-pub struct AptDetector;
+/// Reads the dpkg database directly instead of running `dpkg -S`.
+pub struct AptDetector {
+    root: PathBuf,
+    dpkg: DpkgDatabase,
+}
 
 impl AptDetector {
     pub fn new() -> Self {
-        Self
+        Self::with_root(Path::new("/"))
+    }
+
+    /// Use the dpkg/apt databases below `root` instead of the running system's.
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dpkg: DpkgDatabase::new(root),
+        }
     }
 }
 
@@ -40,43 +57,225 @@ impl PackageManagerDetector for AptDetector {
             return None;
         }
 
-        // Query dpkg to find which package owns this file
-        if let Some((package, version)) = query_dpkg(&ctx.resolved_path.to_string_lossy()) {
-            return Some(DetectionResult {
-                manager_id: self.id().to_string(),
-                manager_name: self.name().to_string(),
-                package_name: Some(package),
-                version: Some(version),
-                confidence: Confidence::High,
-                command_path: ctx.command_path.clone(),
-                resolved_path: ctx.resolved_path.clone(),
-            });
+        if !self.dpkg.exists() {
+            return None;
         }
 
-        None
+        // Look up which installed package owns this file
+        let package = self.dpkg.find_owner(&ctx.resolved_path)?;
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(package.name),
+            version: Some(package.version),
+            details: [("architecture".to_string(), package.architecture)].into(),
+            confidence: Confidence::High,
+            // The dpkg database itself records ownership, so no separate verification is needed
+            verification: Verification::Verified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn info(&self, _ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        // With multi-arch, the same name can be installed once per architecture
+        let architecture = result.details.get("architecture").map(String::as_str);
+        let package = self
+            .dpkg
+            .package(result.package_name.as_deref()?, architecture)?;
+
+        let extended_states = self.root.join("var/lib/apt/extended_states");
+        Some(PackageInfo {
+            description: package.summary.clone(),
+            homepage: package.homepage.clone(),
+            license: None,
+            install_date: self
+                .dpkg
+                .list_file(&package)
+                .and_then(|list| modified_time(&list)),
+            installed_on_request: std::fs::read_to_string(extended_states)
+                .ok()
+                .map(|states| !is_auto_installed(&states, &package.name, &package.architecture)),
+            provides: bin_commands(&self.dpkg.package_files(&package)),
+            ..Default::default()
+        })
     }
 }
 
-fn query_dpkg(path: &str) -> Option<(String, String)> {
-    // dpkg -S /path/to/file returns: package-name: /path/to/file
-    let output = Command::new("dpkg").args(["-S", path]).output().ok()?;
+/// Commands installed into a bin directory, from a package's file list.
+fn bin_commands(files: &[PathBuf]) -> Vec<String> {
+    let mut commands: Vec<String> = files
+        .iter()
+        .filter(|p| {
+            p.parent()
+                .and_then(|d| d.file_name())
+                .is_some_and(|d| d == "bin" || d == "sbin")
+        })
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    commands.sort();
+    commands.dedup();
+    commands
+}
+
+/// apt marks automatically installed packages in `extended_states`:
+/// `Package: foo` / `Architecture: amd64` / `Auto-Installed: 1`. With
+/// multi-arch each installed architecture has its own stanza; `all` packages
+/// are recorded under the native architecture, so they match any.
+fn is_auto_installed(extended_states: &str, package: &str, architecture: &str) -> bool {
+    extended_states.split("\n\n").any(|stanza| {
+        let mut name_matches = false;
+        let mut arch_matches = true;
+        let mut auto = false;
+        for line in stanza.lines() {
+            if let Some(name) = line.strip_prefix("Package: ") {
+                name_matches = name.trim() == package;
+            } else if let Some(arch) = line.strip_prefix("Architecture: ") {
+                arch_matches = architecture == "all" || arch.trim() == architecture;
+            } else if let Some(flag) = line.strip_prefix("Auto-Installed: ") {
+                auto = flag.trim() == "1";
+            }
+        }
+        name_matches && arch_matches && auto
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &str) -> DetectionContext {
+        let path = PathBuf::from(path);
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.clone(),
+            symlink_chain: vec![path.clone()],
+            resolved_path: path,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let admin = dir.path().join("var/lib/dpkg");
+        std::fs::create_dir_all(admin.join("info")).unwrap();
+        std::fs::create_dir_all(dir.path().join("var/lib/apt")).unwrap();
+        std::fs::write(
+            admin.join("status"),
+            "Package: git\nStatus: install ok installed\nArchitecture: amd64\n\
+             Version: 1:2.39.2-1.1\nDescription: fast, scalable, distributed revision control system\n \
+             Long description.\nHomepage: https://git-scm.com/\n",
+        )
+        .unwrap();
+        std::fs::write(
+            admin.join("info/git.list"),
+            "/.\n/usr\n/usr/bin\n/usr/bin/git\n/usr/bin/git-shell\n\
+             /usr/share/doc/git/copyright\n/usr/lib/git-core/git\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("var/lib/apt/extended_states"),
+            "Package: libfoo\nArchitecture: amd64\nAuto-Installed: 1\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detects_from_dpkg_database() {
+        let root = fixture();
+        let detector = AptDetector::with_root(root.path());
+        let result = detector
+            .detect(&make_context("git", "/usr/bin/git"))
+            .unwrap();
+        assert_eq!(result.manager_id, "apt");
+        assert_eq!(result.package_name, Some("git".to_string()));
+        assert_eq!(result.version, Some("1:2.39.2-1.1".to_string()));
+        assert_eq!(result.details["architecture"], "amd64");
+        assert_eq!(result.verification, Verification::Verified);
+    }
+
+    #[test]
+    fn test_unowned_system_path() {
+        let root = fixture();
+        let detector = AptDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("custom", "/usr/bin/custom"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_ignores_non_system_paths() {
+        let root = fixture();
+        let detector = AptDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("git", "/usr/lib/git-core/git"))
+            .is_none());
+    }
 
-    if !output.status.success() {
-        return None;
+    #[test]
+    fn test_info_from_dpkg_database() {
+        let root = fixture();
+        let detector = AptDetector::with_root(root.path());
+        let ctx = make_context("git", "/usr/bin/git");
+        let result = detector.detect(&ctx).unwrap();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("fast, scalable, distributed revision control system")
+        );
+        assert_eq!(info.homepage.as_deref(), Some("https://git-scm.com/"));
+        assert_eq!(info.installed_on_request, Some(true));
+        assert!(info.install_date.is_some());
+        assert_eq!(info.provides, vec!["git", "git-shell"]);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let package_name = stdout.split(':').next()?.trim().to_string();
+    #[test]
+    fn test_info_uses_owning_architecture() {
+        let root = fixture();
+        let admin = root.path().join("var/lib/dpkg");
+        std::fs::write(
+            admin.join("status"),
+            "Package: wine\nStatus: install ok installed\nArchitecture: amd64\n\
+             Version: 8.0~repack-4\nDescription: Windows API implementation (64-bit)\n\n\
+             Package: wine\nStatus: install ok installed\nArchitecture: i386\n\
+             Version: 8.0~repack-4\nDescription: Windows API implementation (32-bit)\n",
+        )
+        .unwrap();
+        std::fs::write(admin.join("info/wine:amd64.list"), "/usr/bin/wine64\n").unwrap();
+        std::fs::write(admin.join("info/wine:i386.list"), "/usr/bin/wine\n").unwrap();
 
-    // Get version with dpkg-query
-    let version_output = Command::new("dpkg-query")
-        .args(["-W", "-f=${Version}", &package_name])
-        .output()
-        .ok()?;
+        let detector = AptDetector::with_root(root.path());
+        let ctx = make_context("wine", "/usr/bin/wine");
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.details["architecture"], "i386");
 
-    let version = String::from_utf8_lossy(&version_output.stdout)
-        .trim()
-        .to_string();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Windows API implementation (32-bit)")
+        );
+        assert_eq!(info.provides, vec!["wine"]);
+    }
 
-    Some((package_name, version))
+    #[test]
+    fn test_is_auto_installed() {
+        let states = "Package: libfoo\nArchitecture: amd64\nAuto-Installed: 1\n\n\
+                      Package: git\nArchitecture: amd64\nAuto-Installed: 0\n";
+        assert!(is_auto_installed(states, "libfoo", "amd64"));
+        assert!(!is_auto_installed(states, "git", "amd64"));
+        assert!(!is_auto_installed(states, "curl", "amd64"));
+    }
+
+    #[test]
+    fn test_is_auto_installed_matches_architecture() {
+        let states = "Package: libfoo\nArchitecture: i386\nAuto-Installed: 1\n\n\
+                      Package: libfoo\nArchitecture: amd64\nAuto-Installed: 0\n\n\
+                      Package: fonts-foo\nArchitecture: amd64\nAuto-Installed: 1\n";
+        assert!(is_auto_installed(states, "libfoo", "i386"));
+        assert!(!is_auto_installed(states, "libfoo", "amd64"));
+        assert!(is_auto_installed(states, "fonts-foo", "all"));
+    }
 }
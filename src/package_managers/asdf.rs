@@ -0,0 +1,493 @@
+//! Shim resolution for asdf. Layout under `ASDF_DATA_DIR` (default `~/.asdf`):
+//!
+//! - `shims/<command>`: script with one `# asdf-plugin: <plugin> <version>`
+//!   comment per installed version that provides the command
+//! - `installs/<plugin>/<version>/bin/<command>`: the real binary
+//!
+//! The version is selected by `ASDF_<PLUGIN>_VERSION` or the nearest
+//! `.tool-versions` file, the same way `asdf exec` does it.
+
+use super::info::{list_commands, modified_time};
+use super::mise::{parse_tool_versions, ToolRequest};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Detector for asdf-managed tools.
+pub struct AsdfDetector {
+    /// `ASDF_DATA_DIR`, or `~/.asdf`
+    data_dir: Option<PathBuf>,
+    home: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+    /// `ASDF_*` environment variables
+    env: HashMap<String, String>,
+}
+
+/// What a shim dispatches to.
+#[derive(Debug, PartialEq)]
+struct ShimTarget {
+    plugin: String,
+    version: Option<String>,
+    binary: Option<PathBuf>,
+    /// `.tool-versions` file or environment variable that selected the version
+    origin: Option<String>,
+}
+
+impl AsdfDetector {
+    pub fn new() -> Self {
+        let env: HashMap<String, String> = std::env::vars()
+            .filter(|(key, value)| key.starts_with("ASDF_") && !value.is_empty())
+            .collect();
+        let home = dirs::home_dir();
+        let data_dir = env
+            .get("ASDF_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".asdf")));
+
+        Self {
+            data_dir,
+            home,
+            cwd: std::env::current_dir().ok(),
+            env,
+        }
+    }
+
+    /// Name of the per-directory version file.
+    fn tool_versions_filename(&self) -> &str {
+        self.env
+            .get("ASDF_DEFAULT_TOOL_VERSIONS_FILENAME")
+            .map(String::as_str)
+            .unwrap_or(".tool-versions")
+    }
+
+    /// The versions selected for `plugin`, checking the environment, then
+    /// `.tool-versions` from the current directory upwards, then the home directory.
+    fn selected_versions(&self, plugin: &str) -> Option<ToolRequest> {
+        let var = format!("ASDF_{}_VERSION", plugin.to_uppercase().replace('-', "_"));
+        if let Some(value) = self.env.get(&var) {
+            return Some(ToolRequest {
+                tool: plugin.to_string(),
+                versions: value.split_whitespace().map(str::to_string).collect(),
+                origin: var,
+            });
+        }
+
+        let filename = self.tool_versions_filename();
+        let dirs = self
+            .cwd
+            .iter()
+            .flat_map(|cwd| cwd.ancestors())
+            .chain(self.home.as_deref());
+        for dir in dirs {
+            let file = dir.join(filename);
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            let request = parse_tool_versions(&content, &file.display().to_string())
+                .into_iter()
+                .find(|r| r.tool == plugin);
+            if request.is_some() {
+                return request;
+            }
+        }
+        None
+    }
+
+    /// Resolve a shimmed command to the plugin and installed version providing it.
+    fn resolve_shim(&self, data_dir: &Path, shim: &Path) -> Option<ShimTarget> {
+        let command = shim.file_name()?.to_string_lossy().to_string();
+        let content = std::fs::read_to_string(shim).ok()?;
+        let provided = shim_plugins(&content);
+
+        let mut plugins: Vec<&str> = provided.iter().map(|(p, _)| p.as_str()).collect();
+        plugins.dedup();
+
+        for plugin in &plugins {
+            let Some(request) = self.selected_versions(plugin) else {
+                continue;
+            };
+            for version in &request.versions {
+                let target = |version: &str, binary: Option<PathBuf>| ShimTarget {
+                    plugin: plugin.to_string(),
+                    version: Some(version.to_string()),
+                    binary,
+                    origin: Some(request.origin.clone()),
+                };
+
+                if version == "system" {
+                    return Some(target(version, None));
+                }
+                let install_dir = match version.strip_prefix("path:") {
+                    Some(path) => PathBuf::from(path),
+                    None => install_dir(data_dir, plugin, version),
+                };
+                let binary = install_dir.join("bin").join(&command);
+                if binary.is_file() {
+                    return Some(target(version, Some(binary)));
+                }
+            }
+        }
+
+        // No version selected: asdf refuses to run, but the shim still names its plugins
+        plugins.first().map(|plugin| ShimTarget {
+            plugin: plugin.to_string(),
+            version: None,
+            binary: None,
+            origin: None,
+        })
+    }
+
+    fn result(
+        &self,
+        ctx: &DetectionContext,
+        plugin: Option<String>,
+        version: Option<String>,
+        resolved_path: PathBuf,
+        confidence: Confidence,
+    ) -> DetectionResult {
+        DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: plugin,
+            version,
+            details: Default::default(),
+            confidence,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path,
+        }
+    }
+
+    fn find_shim<'a>(&self, data_dir: &Path, ctx: &'a DetectionContext) -> Option<&'a PathBuf> {
+        let shims = data_dir.join("shims");
+        ctx.command_files()
+            .find(|p| p.parent() == Some(shims.as_path()))
+    }
+}
+
+/// `installs/<plugin>/<version>`; `ref:<rev>` versions are installed as `ref-<rev>`.
+fn install_dir(data_dir: &Path, plugin: &str, version: &str) -> PathBuf {
+    data_dir
+        .join("installs")
+        .join(plugin)
+        .join(version.replace(':', "-"))
+}
+
+/// `(plugin, version)` pairs from the `# asdf-plugin:` comments of a shim.
+fn shim_plugins(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("# asdf-plugin:"))
+        .filter_map(|rest| {
+            let mut parts = rest.split_whitespace();
+            let plugin = parts.next()?.to_string();
+            Some((plugin, parts.next().unwrap_or_default().to_string()))
+        })
+        .collect()
+}
+
+impl PackageManagerDetector for AsdfDetector {
+    fn id(&self) -> &'static str {
+        "asdf"
+    }
+
+    fn name(&self) -> &str {
+        "asdf"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        matches!(platform, Platform::MacOS | Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        90 // Same as mise, since asdf also uses shims
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let data_dir = self.data_dir.as_ref()?;
+        let installs = data_dir.join("installs");
+
+        // Medium until verification finds the shim's plugin installed
+        if let Some(shim) = self.find_shim(data_dir, ctx) {
+            return Some(match self.resolve_shim(data_dir, shim) {
+                Some(ShimTarget {
+                    plugin,
+                    version,
+                    binary: Some(binary),
+                    ..
+                }) => self.result(ctx, Some(plugin), version, binary, Confidence::Medium),
+                Some(target) => self.result(
+                    ctx,
+                    Some(target.plugin),
+                    target.version,
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ),
+                None => self.result(
+                    ctx,
+                    None,
+                    None,
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ),
+            });
+        }
+
+        // Called directly as installs/<plugin>/<version>/bin/<command>
+        for path in ctx.command_files() {
+            if let Ok(relative) = path.strip_prefix(&installs) {
+                let mut parts = relative.iter().map(|p| p.to_string_lossy().to_string());
+                let plugin = parts.next()?;
+                let version = parts.next()?;
+                return Some(self.result(
+                    ctx,
+                    Some(plugin),
+                    Some(version),
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ));
+            }
+        }
+
+        None
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let (Some(data_dir), Some(plugin), Some(version)) = (
+            self.data_dir.as_ref(),
+            result.package_name.as_ref(),
+            result.version.as_ref(),
+        ) else {
+            return Verification::Unverified;
+        };
+
+        // A shim must list the version in its `# asdf-plugin:` header
+        if let Some(shim) = self.find_shim(data_dir, ctx) {
+            let listed = std::fs::read_to_string(shim)
+                .map(|content| shim_plugins(&content))
+                .unwrap_or_default()
+                .iter()
+                .any(|(p, v)| p == plugin && v == version);
+            if !listed {
+                return Verification::Unverified;
+            }
+        }
+
+        let install_dir = install_dir(data_dir, plugin, version);
+        if install_dir.join("bin").join(&ctx.command_name).is_file() {
+            Verification::Verified
+        } else {
+            Verification::Unverified
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let data_dir = self.data_dir.as_ref()?;
+        let plugin = result.package_name.as_ref()?;
+        let version = result.version.as_ref()?;
+        let install_dir = install_dir(data_dir, plugin, version);
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&install_dir),
+            installed_on_request: Some(true),
+            provides: list_commands(&install_dir.join("bin")),
+            ..Default::default()
+        };
+
+        if let Some(shim) = self.find_shim(data_dir, ctx) {
+            if let Some(origin) = self
+                .resolve_shim(data_dir, shim)
+                .and_then(|target| target.origin)
+            {
+                info.extra.insert("selected_by".to_string(), origin);
+            }
+
+            let mut plugins: Vec<String> = std::fs::read_to_string(shim)
+                .map(|content| shim_plugins(&content))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(plugin, _)| plugin)
+                .collect();
+            plugins.dedup();
+            if plugins.len() > 1 {
+                info.extra
+                    .insert("shim_plugins".to_string(), plugins.join(", "));
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.to_path_buf(),
+            symlink_chain: vec![path.to_path_buf()],
+            resolved_path: path.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn detector(home: &Path, cwd: &Path, env: &[(&str, &str)]) -> AsdfDetector {
+        AsdfDetector {
+            data_dir: Some(home.join(".asdf")),
+            home: Some(home.to_path_buf()),
+            cwd: Some(cwd.to_path_buf()),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// An asdf data dir with nodejs 18.19.0 and 20.11.0 installed, 18.19.0 as
+    /// the global version, and a `npx` shim shared by both.
+    fn fixture() -> tempfile::TempDir {
+        let home = tempfile::tempdir().unwrap();
+        let data_dir = home.path().join(".asdf");
+        std::fs::create_dir_all(data_dir.join("shims")).unwrap();
+        std::fs::write(
+            data_dir.join("shims/npx"),
+            "#!/usr/bin/env bash\n\
+             # asdf-plugin: nodejs 18.19.0\n\
+             # asdf-plugin: nodejs 20.11.0\n\
+             exec /home/u/.asdf/bin/asdf exec \"npx\" \"$@\" # asdf_allow: ' asdf '\n",
+        )
+        .unwrap();
+        for version in ["18.19.0", "20.11.0"] {
+            let bin = data_dir.join("installs/nodejs").join(version).join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            std::fs::write(bin.join("npx"), "").unwrap();
+            std::fs::write(bin.join("node"), "").unwrap();
+        }
+        std::fs::write(home.path().join(".tool-versions"), "nodejs 18.19.0\n").unwrap();
+        std::fs::create_dir_all(home.path().join("project/src")).unwrap();
+        home
+    }
+
+    #[test]
+    fn test_global_tool_versions() {
+        let home = fixture();
+        let shim = home.path().join(".asdf/shims/npx");
+        let detector = detector(home.path(), home.path(), &[]);
+        let ctx = make_context("npx", &shim);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "asdf");
+        assert_eq!(result.package_name, Some("nodejs".to_string()));
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+        assert_eq!(
+            result.resolved_path,
+            home.path().join(".asdf/installs/nodejs/18.19.0/bin/npx")
+        );
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[test]
+    fn test_project_tool_versions_walks_up() {
+        let home = fixture();
+        std::fs::write(
+            home.path().join("project/.tool-versions"),
+            "python 3.12.1\nnodejs 20.11.0 18.19.0 # fallback\n",
+        )
+        .unwrap();
+        let shim = home.path().join(".asdf/shims/npx");
+        let detector = detector(home.path(), &home.path().join("project/src"), &[]);
+        let ctx = make_context("npx", &shim);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert!(info.extra["selected_by"].ends_with("project/.tool-versions"));
+        assert_eq!(info.provides, vec!["node", "npx"]);
+    }
+
+    #[test]
+    fn test_environment_variable_wins() {
+        let home = fixture();
+        let shim = home.path().join(".asdf/shims/npx");
+        let detector = detector(
+            home.path(),
+            home.path(),
+            &[("ASDF_NODEJS_VERSION", "20.11.0")],
+        );
+        let result = detector.detect(&make_context("npx", &shim)).unwrap();
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+    }
+
+    #[test]
+    fn test_unselected_version_is_medium() {
+        let home = fixture();
+        std::fs::remove_file(home.path().join(".tool-versions")).unwrap();
+        let shim = home.path().join(".asdf/shims/npx");
+        let detector = detector(home.path(), home.path(), &[]);
+        let ctx = make_context("npx", &shim);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("nodejs".to_string()));
+        assert_eq!(result.version, None);
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_version_missing_from_shim_header() {
+        let home = fixture();
+        let shim = home.path().join(".asdf/shims/npx");
+        std::fs::write(
+            &shim,
+            "#!/usr/bin/env bash
+# asdf-plugin: nodejs 20.11.0
+exec asdf exec \"npx\" \"$@\"\n",
+        )
+        .unwrap();
+        let detector = detector(home.path(), home.path(), &[]);
+        let ctx = make_context("npx", &shim);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_direct_install_path() {
+        let home = fixture();
+        let node = home.path().join(".asdf/installs/nodejs/20.11.0/bin/node");
+        let detector = detector(home.path(), home.path(), &[]);
+        let ctx = make_context("node", &node);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("nodejs".to_string()));
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let home = fixture();
+        let detector = detector(home.path(), home.path(), &[]);
+        assert!(detector
+            .detect(&make_context("ls", Path::new("/usr/bin/ls")))
+            .is_none());
+    }
+
+    #[test]
+    fn test_shim_plugins() {
+        let content = "#!/usr/bin/env bash\n# asdf-plugin: python 3.12.1\n# asdf-plugin: conda miniforge3-23.3.1\nexec asdf exec \"pip\" \"$@\"\n";
+        assert_eq!(
+            shim_plugins(content),
+            vec![
+                ("python".to_string(), "3.12.1".to_string()),
+                ("conda".to_string(), "miniforge3-23.3.1".to_string()),
+            ]
+        );
+    }
+}
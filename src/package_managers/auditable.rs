@@ -0,0 +1,306 @@
+use super::binary::{read_section, SectionName};
+use super::cargo::is_cargo_bin_path;
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use flate2::read::ZlibDecoder;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+/// Section `cargo auditable` links into the binary: the dependency list as
+/// zlib-compressed JSON. The name is the same in every format.
+const DEP_SECTION: SectionName = SectionName {
+    elf: ".dep-v0",
+    macho: ("__DATA", ".dep-v0"),
+    pe: ".dep-v0",
+};
+
+/// Decompressed size limit, as in the reference implementation.
+const MAX_JSON_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Fallback detector for Rust binaries built with `cargo auditable`.
+/// The embedded dependency list names the root crate, which identifies tools
+/// that were copied into place by hand. Low priority: only used when nothing
+/// else claims the path.
+pub struct AuditableDetector;
+
+impl AuditableDetector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl PackageManagerDetector for AuditableDetector {
+    fn id(&self) -> &'static str {
+        "rust_binary"
+    }
+
+    fn name(&self) -> &str {
+        "Rust binary (cargo-auditable)"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true
+    }
+
+    fn priority(&self) -> i32 {
+        15 // Just above the system fallback
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        // `CargoDetector` reports binaries in Cargo's bin directory
+        if ctx.symlink_chain.iter().any(|p| is_cargo_bin_path(p)) {
+            return None;
+        }
+        let audit = read_audit_data(&ctx.resolved_path)?;
+        let root = audit.root()?;
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(root.name.clone()),
+            version: Some(root.version.clone()),
+            details: Default::default(),
+            confidence: Confidence::Low,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let audit = read_audit_data(&ctx.resolved_path)?;
+        let mut info = PackageInfo {
+            install_date: modified_time(&ctx.resolved_path),
+            source: audit.root().map(|root| root.source.clone()),
+            ..Default::default()
+        };
+        audit.add_details(&mut info);
+        Some(info)
+    }
+}
+
+/// The `.dep-v0` JSON document.
+#[derive(Debug, Deserialize)]
+pub(super) struct AuditData {
+    packages: Vec<AuditPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditPackage {
+    name: String,
+    version: String,
+    /// `crates.io`, `git`, `local`, `registry` or `other`
+    source: String,
+    /// `build` for build-only dependencies; omitted for runtime ones
+    #[serde(default)]
+    kind: Option<String>,
+    /// Indices into `packages`
+    #[serde(default)]
+    dependencies: Vec<usize>,
+    #[serde(default)]
+    root: bool,
+}
+
+impl AuditData {
+    /// The crate the binary was built from. Older writers did not mark it,
+    /// so fall back to the package nothing depends on.
+    fn root(&self) -> Option<&AuditPackage> {
+        self.packages.iter().find(|p| p.root).or_else(|| {
+            let depended_on: HashSet<usize> = self
+                .packages
+                .iter()
+                .flat_map(|p| p.dependencies.iter().copied())
+                .collect();
+            let mut roots = (0..self.packages.len()).filter(|i| !depended_on.contains(i));
+            match (roots.next(), roots.next()) {
+                (Some(only), None) => self.packages.get(only),
+                _ => None,
+            }
+        })
+    }
+
+    /// Add the dependency count and tree to `info.extra`.
+    pub(super) fn add_details(&self, info: &mut PackageInfo) {
+        let Some(root) = self.root() else {
+            return;
+        };
+        let count = self.packages.len() - 1;
+        if count == 0 {
+            return;
+        }
+        info.extra
+            .insert("dependencies".to_string(), format!("{count} crates"));
+        let mut lines = Vec::new();
+        let mut seen = HashSet::new();
+        self.render_tree(&root.dependencies, "", &mut seen, &mut lines);
+        info.extra
+            .insert("dependency_tree".to_string(), lines.join("\n"));
+    }
+
+    /// Render dependencies like `cargo tree`, marking repeated subtrees `(*)`.
+    fn render_tree(
+        &self,
+        deps: &[usize],
+        prefix: &str,
+        seen: &mut HashSet<usize>,
+        lines: &mut Vec<String>,
+    ) {
+        let deps: Vec<(usize, &AuditPackage)> = deps
+            .iter()
+            .filter_map(|&i| Some((i, self.packages.get(i)?)))
+            .collect();
+        for (n, &(index, package)) in deps.iter().enumerate() {
+            let last = n + 1 == deps.len();
+            let mut line = format!(
+                "{prefix}{} {} v{}",
+                if last { "└──" } else { "├──" },
+                package.name,
+                package.version
+            );
+            if package.kind.as_deref() == Some("build") {
+                line.push_str(" (build)");
+            }
+            let first_visit = seen.insert(index);
+            if !first_visit && !package.dependencies.is_empty() {
+                line.push_str(" (*)");
+            }
+            lines.push(line);
+            if first_visit {
+                let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+                self.render_tree(&package.dependencies, &child_prefix, seen, lines);
+            }
+        }
+    }
+}
+
+/// Read and decompress the dependency list from a binary.
+pub(super) fn read_audit_data(path: &Path) -> Option<AuditData> {
+    let compressed = read_section(path, &DEP_SECTION)?;
+    let mut json = Vec::new();
+    ZlibDecoder::new(compressed.as_slice())
+        .take(MAX_JSON_SIZE)
+        .read_to_end(&mut json)
+        .ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::binary::tests::build_elf;
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    /// ripgrep's dependency list, trimmed: `memchr` is shared, `cc` is a
+    /// build dependency.
+    const RIPGREP_DEPS: &str = r#"{"packages":[
+        {"name":"cc","version":"1.0.83","source":"crates.io","kind":"build"},
+        {"name":"grep","version":"0.3.1","source":"crates.io","dependencies":[3]},
+        {"name":"regex","version":"1.10.2","source":"crates.io","dependencies":[3]},
+        {"name":"memchr","version":"2.7.1","source":"crates.io"},
+        {"name":"jemallocator","version":"0.5.4","source":"crates.io","dependencies":[0]},
+        {"name":"ripgrep","version":"14.1.0","source":"crates.io","dependencies":[1,4,2],"root":true}
+    ]}"#;
+
+    fn write_auditable_binary(path: &Path, json: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let section = encoder.finish().unwrap();
+        std::fs::write(path, build_elf(&[(".dep-v0", &section)], &[])).unwrap();
+    }
+
+    fn make_context(command: &str, paths: Vec<&Path>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_detects_root_crate() {
+        let dir = tempfile::tempdir().unwrap();
+        let rg = dir.path().join("usr/local/bin/rg");
+        write_auditable_binary(&rg, RIPGREP_DEPS);
+
+        let detector = AuditableDetector::new();
+        let ctx = make_context("rg", vec![&rg]);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "rust_binary");
+        assert_eq!(result.package_name, Some("ripgrep".to_string()));
+        assert_eq!(result.version, Some("14.1.0".to_string()));
+        assert_eq!(result.confidence, Confidence::Low);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.source, Some("crates.io".to_string()));
+        assert_eq!(info.extra["dependencies"], "5 crates");
+        assert_eq!(
+            info.extra["dependency_tree"],
+            "├── grep v0.3.1\n\
+             │   └── memchr v2.7.1\n\
+             ├── jemallocator v0.5.4\n\
+             │   └── cc v1.0.83 (build)\n\
+             └── regex v1.10.2\n    \
+             └── memchr v2.7.1"
+        );
+    }
+
+    #[test]
+    fn test_marks_repeated_subtrees() {
+        let json = r#"{"packages":[
+            {"name":"a","version":"1.0.0","source":"crates.io","dependencies":[1]},
+            {"name":"b","version":"1.0.0","source":"crates.io"},
+            {"name":"tool","version":"0.1.0","source":"local","dependencies":[0,0]}
+        ]}"#;
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tool");
+        write_auditable_binary(&tool, json);
+
+        // No `root` flag: the crate nothing depends on is the root
+        let audit = read_audit_data(&tool).unwrap();
+        assert_eq!(audit.root().unwrap().name, "tool");
+        let mut info = PackageInfo::default();
+        audit.add_details(&mut info);
+        assert_eq!(
+            info.extra["dependency_tree"],
+            "├── a v1.0.0\n│   └── b v1.0.0\n└── a v1.0.0 (*)"
+        );
+    }
+
+    #[test]
+    fn test_leaves_cargo_bin_to_cargo() {
+        let dir = tempfile::tempdir().unwrap();
+        let rg = dir.path().join(".cargo/bin/rg");
+        write_auditable_binary(&rg, RIPGREP_DEPS);
+        let ctx = make_context("rg", vec![&rg]);
+        assert!(AuditableDetector::new().detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_ignores_other_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tool");
+        std::fs::write(&tool, build_elf(&[(".text", b"\x90")], &[])).unwrap();
+        assert!(AuditableDetector::new()
+            .detect(&make_context("tool", vec![&tool]))
+            .is_none());
+
+        // Not zlib data
+        std::fs::write(&tool, build_elf(&[(".dep-v0", b"{}")], &[])).unwrap();
+        assert!(read_audit_data(&tool).is_none());
+    }
+}
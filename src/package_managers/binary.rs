@@ -0,0 +1,507 @@
+//! Minimal readers for executable file formats (ELF, Mach-O, PE), used to
+//! pull metadata sections out of binaries without loading the whole file.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Upper bound for a section we are willing to read into memory.
+const MAX_SECTION_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Read `len` bytes at `offset`.
+pub(super) fn read_at(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    let mut buf = vec![0; len];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
+
+/// Integer decoding for the file's byte order.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Endian {
+    pub(super) little: bool,
+}
+
+impl Endian {
+    pub(super) fn u16(self, b: &[u8], at: usize) -> Option<u16> {
+        let bytes: [u8; 2] = b.get(at..at + 2)?.try_into().ok()?;
+        Some(if self.little {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    pub(super) fn u32(self, b: &[u8], at: usize) -> Option<u32> {
+        let bytes: [u8; 4] = b.get(at..at + 4)?.try_into().ok()?;
+        Some(if self.little {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    pub(super) fn u64(self, b: &[u8], at: usize) -> Option<u64> {
+        let bytes: [u8; 8] = b.get(at..at + 8)?.try_into().ok()?;
+        Some(if self.little {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+}
+
+/// Where a section lives in each executable format.
+pub(super) struct SectionName {
+    pub(super) elf: &'static str,
+    /// Segment and section name
+    pub(super) macho: (&'static str, &'static str),
+    pub(super) pe: &'static str,
+}
+
+/// Read a section from an ELF, Mach-O (including universal) or PE file.
+pub(super) fn read_section(path: &Path, name: &SectionName) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let header = read_at(&mut file, 0, 8)?;
+    if is_elf(&header) {
+        drop(file);
+        return read_elf_section(path, name.elf);
+    }
+    if header.starts_with(b"MZ") {
+        return read_pe_section(&mut file, name.pe);
+    }
+    let (segment, section) = name.macho;
+    match header[..4] {
+        // Universal binary: every slice carries the same metadata, so read the first
+        [0xca, 0xfe, 0xba, 0xbe] | [0xca, 0xfe, 0xba, 0xbf] => {
+            let is_64 = header[3] == 0xbf;
+            let big = Endian { little: false };
+            let nfat = big.u32(&header, 4)?;
+            // Java class files share the magic; they have a version number here instead
+            if nfat == 0 || nfat > 32 {
+                return None;
+            }
+            let arch = read_at(&mut file, 8, if is_64 { 32 } else { 20 })?;
+            let offset = if is_64 {
+                big.u64(&arch, 8)?
+            } else {
+                big.u32(&arch, 8)? as u64
+            };
+            read_macho_section(&mut file, offset, segment, section)
+        }
+        _ => read_macho_section(&mut file, 0, segment, section),
+    }
+}
+
+/// Read a section from the Mach-O image starting at `base` in the file.
+fn read_macho_section(file: &mut File, base: u64, segment: &str, section: &str) -> Option<Vec<u8>> {
+    let header = read_at(file, base, 32)?;
+    let (is_64, little) = match header[..4] {
+        [0xcf, 0xfa, 0xed, 0xfe] => (true, true),
+        [0xfe, 0xed, 0xfa, 0xcf] => (true, false),
+        [0xce, 0xfa, 0xed, 0xfe] => (false, true),
+        [0xfe, 0xed, 0xfa, 0xce] => (false, false),
+        _ => return None,
+    };
+    let endian = Endian { little };
+    let ncmds = endian.u32(&header, 16)?;
+    let sizeofcmds = endian.u32(&header, 20)? as u64;
+    if sizeofcmds > MAX_SECTION_SIZE {
+        return None;
+    }
+    let header_size = if is_64 { 32 } else { 28 };
+    let commands = read_at(file, base + header_size, sizeofcmds as usize)?;
+
+    // LC_SEGMENT_64 / LC_SEGMENT, and the layout of their section entries
+    let (segment_cmd, segment_size, section_size) =
+        if is_64 { (0x19, 72, 80) } else { (0x1, 56, 68) };
+    let name_matches =
+        |bytes: &[u8], name: &str| bytes.split(|&b| b == 0).next() == Some(name.as_bytes());
+
+    let mut at = 0usize;
+    for _ in 0..ncmds {
+        let cmd = endian.u32(&commands, at)?;
+        let cmdsize = endian.u32(&commands, at + 4)? as usize;
+        if cmd == segment_cmd && name_matches(commands.get(at + 8..at + 24)?, segment) {
+            let nsects = endian.u32(&commands, at + segment_size - 8)? as usize;
+            for i in 0..nsects {
+                let entry = at + segment_size + i * section_size;
+                if !name_matches(commands.get(entry..entry + 16)?, section) {
+                    continue;
+                }
+                let (size, offset) = if is_64 {
+                    (
+                        endian.u64(&commands, entry + 40)?,
+                        endian.u32(&commands, entry + 48)?,
+                    )
+                } else {
+                    (
+                        endian.u32(&commands, entry + 36)? as u64,
+                        endian.u32(&commands, entry + 40)?,
+                    )
+                };
+                if size > MAX_SECTION_SIZE {
+                    return None;
+                }
+                return read_at(file, base + offset as u64, size as usize);
+            }
+        }
+        if cmdsize == 0 {
+            return None;
+        }
+        at += cmdsize;
+    }
+    None
+}
+
+/// Read a section from a PE (Windows) image.
+fn read_pe_section(file: &mut File, name: &str) -> Option<Vec<u8>> {
+    let le = Endian { little: true };
+    let dos = read_at(file, 0, 64)?;
+    let pe_offset = le.u32(&dos, 0x3c)? as u64;
+    let coff = read_at(file, pe_offset, 24)?;
+    if !coff.starts_with(b"PE\0\0") {
+        return None;
+    }
+    let nsections = le.u16(&coff, 6)? as usize;
+    let optional_size = le.u16(&coff, 20)? as u64;
+    let table = read_at(file, pe_offset + 24 + optional_size, nsections * 40)?;
+
+    for entry in table.chunks_exact(40) {
+        if entry[..8].split(|&b| b == 0).next() != Some(name.as_bytes()) {
+            continue;
+        }
+        let size = le.u32(entry, 16)? as u64;
+        let offset = le.u32(entry, 20)? as u64;
+        if size > MAX_SECTION_SIZE {
+            return None;
+        }
+        return read_at(file, offset, size as usize);
+    }
+    None
+}
+
+/// Whether the file starts with the ELF magic.
+pub(super) fn is_elf(header: &[u8]) -> bool {
+    header.starts_with(b"\x7fELF")
+}
+
+/// The fields of an ELF file header needed to find its sections.
+struct ElfHeader {
+    is_64: bool,
+    endian: Endian,
+    /// Offset of the section header table
+    shoff: u64,
+    shentsize: u16,
+    shnum: u16,
+    /// Index of the section holding section names
+    shstrndx: u16,
+}
+
+impl ElfHeader {
+    fn read(file: &mut File) -> Option<Self> {
+        let header = read_at(file, 0, 64)?;
+        if !is_elf(&header) {
+            return None;
+        }
+
+        let is_64 = header[4] == 2;
+        let endian = Endian {
+            little: header[5] == 1,
+        };
+        let (shoff, shentsize, shnum, shstrndx) = if is_64 {
+            (
+                endian.u64(&header, 0x28)?,
+                endian.u16(&header, 0x3A)?,
+                endian.u16(&header, 0x3C)?,
+                endian.u16(&header, 0x3E)?,
+            )
+        } else {
+            (
+                endian.u32(&header, 0x20)? as u64,
+                endian.u16(&header, 0x2E)?,
+                endian.u16(&header, 0x30)?,
+                endian.u16(&header, 0x32)?,
+            )
+        };
+        Some(Self {
+            is_64,
+            endian,
+            shoff,
+            shentsize,
+            shnum,
+            shstrndx,
+        })
+    }
+
+    fn section_table_size(&self) -> Option<u64> {
+        u64::from(self.shentsize).checked_mul(u64::from(self.shnum))
+    }
+}
+
+/// Where the ELF file ends and any appended data (an AppImage's filesystem)
+/// begins. Linkers put the section header table last.
+pub(super) fn elf_size(path: &Path) -> Option<u64> {
+    let header = ElfHeader::read(&mut File::open(path).ok()?)?;
+    header.shoff.checked_add(header.section_table_size()?)
+}
+
+/// Read the contents of the ELF section called `name`.
+pub(super) fn read_elf_section(path: &Path, name: &str) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let header = ElfHeader::read(&mut file)?;
+    let table_size = header.section_table_size()?;
+    let ElfHeader {
+        is_64,
+        endian,
+        shoff,
+        shentsize,
+        shnum,
+        shstrndx,
+    } = header;
+    if shnum == 0 || shstrndx >= shnum {
+        return None;
+    }
+    if table_size > MAX_SECTION_SIZE {
+        return None;
+    }
+    let table = read_at(&mut file, shoff, table_size as usize)?;
+    // (name offset, file offset, size) of each section header
+    let section = |i: usize| -> Option<(u32, u64, u64)> {
+        let entry = table.get(i * shentsize as usize..(i + 1) * shentsize as usize)?;
+        if is_64 {
+            Some((
+                endian.u32(entry, 0x00)?,
+                endian.u64(entry, 0x18)?,
+                endian.u64(entry, 0x20)?,
+            ))
+        } else {
+            Some((
+                endian.u32(entry, 0x00)?,
+                endian.u32(entry, 0x10)? as u64,
+                endian.u32(entry, 0x14)? as u64,
+            ))
+        }
+    };
+
+    let (_, strtab_offset, strtab_size) = section(shstrndx as usize)?;
+    if strtab_size > MAX_SECTION_SIZE {
+        return None;
+    }
+    let strtab = read_at(&mut file, strtab_offset, strtab_size as usize)?;
+
+    for i in 0..shnum as usize {
+        let (name_offset, offset, size) = section(i)?;
+        let section_name = strtab
+            .get(name_offset as usize..)?
+            .split(|&b| b == 0)
+            .next()?;
+        if section_name == name.as_bytes() {
+            if size > MAX_SECTION_SIZE {
+                return None;
+            }
+            return read_at(&mut file, offset, size as usize);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Build a minimal little-endian ELF64 file containing the given sections.
+    pub(in crate::package_managers) fn build_elf(
+        sections: &[(&str, &[u8])],
+        ident_pad: &[u8],
+    ) -> Vec<u8> {
+        let mut strtab = vec![0u8];
+        let mut name_offsets = Vec::new();
+        for (name, _) in sections {
+            name_offsets.push(strtab.len() as u32);
+            strtab.extend_from_slice(name.as_bytes());
+            strtab.push(0);
+        }
+        let shstrtab_name = strtab.len() as u32;
+        strtab.extend_from_slice(b".shstrtab\0");
+
+        let mut data = vec![0u8; 64];
+        data[..4].copy_from_slice(b"\x7fELF");
+        data[4] = 2; // 64-bit
+        data[5] = 1; // little endian
+        data[8..8 + ident_pad.len()].copy_from_slice(ident_pad);
+
+        let mut offsets = Vec::new();
+        for (_, contents) in sections {
+            offsets.push(data.len() as u64);
+            data.extend_from_slice(contents);
+        }
+        let strtab_offset = data.len() as u64;
+        data.extend_from_slice(&strtab);
+
+        let shoff = data.len() as u64;
+        let shnum = sections.len() as u16 + 2;
+        let push_header = |data: &mut Vec<u8>, name: u32, offset: u64, size: u64| {
+            let mut entry = vec![0u8; 64];
+            entry[0..4].copy_from_slice(&name.to_le_bytes());
+            entry[0x18..0x20].copy_from_slice(&offset.to_le_bytes());
+            entry[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&entry);
+        };
+        push_header(&mut data, 0, 0, 0); // SHN_UNDEF
+        for (i, (_, contents)) in sections.iter().enumerate() {
+            push_header(
+                &mut data,
+                name_offsets[i],
+                offsets[i],
+                contents.len() as u64,
+            );
+        }
+        push_header(&mut data, shstrtab_name, strtab_offset, strtab.len() as u64);
+
+        data[0x28..0x30].copy_from_slice(&shoff.to_le_bytes());
+        data[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+        data[0x3C..0x3E].copy_from_slice(&shnum.to_le_bytes());
+        data[0x3E..0x40].copy_from_slice(&(shnum - 1).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_reads_named_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bin");
+        std::fs::write(
+            &path,
+            build_elf(&[(".text", b"\x90\x90"), (".note.test", b"hello")], &[]),
+        )
+        .unwrap();
+
+        assert_eq!(read_elf_section(&path, ".note.test").unwrap(), b"hello");
+        assert_eq!(read_elf_section(&path, ".text").unwrap(), b"\x90\x90");
+        assert!(read_elf_section(&path, ".missing").is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_section_header_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bin");
+        let elf = build_elf(&[(".note.test", b"hello")], &[]);
+
+        // Header table claims 0xFFFF entries of 0xFFFF bytes
+        let mut oversized = elf.clone();
+        oversized[0x3A..0x3C].copy_from_slice(&u16::MAX.to_le_bytes());
+        oversized[0x3C..0x3E].copy_from_slice(&u16::MAX.to_le_bytes());
+        std::fs::write(&path, oversized).unwrap();
+        assert!(read_elf_section(&path, ".note.test").is_none());
+
+        // File ends inside the header table
+        std::fs::write(&path, &elf[..elf.len() - 10]).unwrap();
+        assert!(read_elf_section(&path, ".note.test").is_none());
+    }
+
+    /// Build a minimal little-endian 64-bit Mach-O file with one section.
+    fn build_macho(segment: &str, section: &str, contents: &[u8]) -> Vec<u8> {
+        let name = |s: &str| {
+            let mut bytes = [0u8; 16];
+            bytes[..s.len()].copy_from_slice(s.as_bytes());
+            bytes
+        };
+        let sizeofcmds = 72 + 80;
+        let data_offset = 32 + sizeofcmds as u32;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0xcf, 0xfa, 0xed, 0xfe]);
+        data.extend_from_slice(&[0u8; 12]); // cputype, cpusubtype, filetype
+        data.extend_from_slice(&1u32.to_le_bytes()); // ncmds
+        data.extend_from_slice(&(sizeofcmds as u32).to_le_bytes());
+        data.extend_from_slice(&[0u8; 8]); // flags, reserved
+
+        data.extend_from_slice(&0x19u32.to_le_bytes());
+        data.extend_from_slice(&(sizeofcmds as u32).to_le_bytes());
+        data.extend_from_slice(&name(segment));
+        data.extend_from_slice(&[0u8; 40]); // addresses, sizes and protections
+        data.extend_from_slice(&1u32.to_le_bytes()); // nsects
+        data.extend_from_slice(&0u32.to_le_bytes()); // flags
+
+        data.extend_from_slice(&name(section));
+        data.extend_from_slice(&name(segment));
+        data.extend_from_slice(&0u64.to_le_bytes()); // addr
+        data.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        data.extend_from_slice(&data_offset.to_le_bytes());
+        data.extend_from_slice(&[0u8; 28]);
+
+        data.extend_from_slice(contents);
+        data
+    }
+
+    /// Build a minimal PE file with one section.
+    fn build_pe(section: &str, contents: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3c..0x40].copy_from_slice(&64u32.to_le_bytes());
+        data.extend_from_slice(b"PE\0\0");
+        let mut coff = [0u8; 20];
+        coff[2..4].copy_from_slice(&1u16.to_le_bytes()); // sections
+        data.extend_from_slice(&coff); // no optional header
+
+        let mut entry = [0u8; 40];
+        entry[..section.len()].copy_from_slice(section.as_bytes());
+        entry[16..20].copy_from_slice(&(contents.len() as u32).to_le_bytes());
+        entry[20..24].copy_from_slice(&(data.len() as u32 + 40).to_le_bytes());
+        data.extend_from_slice(&entry);
+        data.extend_from_slice(contents);
+        data
+    }
+
+    const TEST_SECTION: SectionName = SectionName {
+        elf: ".note.test",
+        macho: ("__DATA", "__test"),
+        pe: ".test",
+    };
+
+    #[test]
+    fn test_reads_section_from_each_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("elf", build_elf(&[(".note.test", b"hello")], &[])),
+            ("macho", build_macho("__DATA", "__test", b"hello")),
+            ("pe", build_pe(".test", b"hello")),
+        ];
+        for (name, contents) in files {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            assert_eq!(
+                read_section(&path, &TEST_SECTION).as_deref(),
+                Some(&b"hello"[..]),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reads_universal_macho() {
+        let dir = tempfile::tempdir().unwrap();
+        let slice = build_macho("__DATA", "__test", b"fat");
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0xca, 0xfe, 0xba, 0xbe]);
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(&[0u8; 8]); // cputype, cpusubtype
+        data.extend_from_slice(&4096u32.to_be_bytes()); // offset
+        data.extend_from_slice(&(slice.len() as u32).to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes()); // align
+        data.resize(4096, 0);
+        data.extend_from_slice(&slice);
+
+        let path = dir.path().join("universal");
+        std::fs::write(&path, data).unwrap();
+        assert_eq!(read_section(&path, &TEST_SECTION).unwrap(), b"fat");
+    }
+
+    #[test]
+    fn test_rejects_non_elf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("script");
+        std::fs::write(&path, "#!/bin/sh\necho hi\n".repeat(8)).unwrap();
+        assert!(read_elf_section(&path, ".text").is_none());
+    }
+}
@@ -1,5 +1,5 @@
-use super::node_global::{detect_node_global, SKIP_BIN};
-use super::{DetectionContext, DetectionResult, PackageManagerDetector};
+use super::node_global::{detect_node_global, node_global_info, verify_node_global, SKIP_BIN};
+use super::{DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for bun global packages.
@@ -31,6 +31,14 @@ impl PackageManagerDetector for BunGlobalDetector {
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
         detect_node_global(ctx, self.id(), self.name(), is_bun_global_path, SKIP_BIN)
     }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        verify_node_global(ctx, result)
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        node_global_info(ctx, result)
+    }
 }
 
 fn is_bun_global_path(path: &str) -> bool {
@@ -56,6 +64,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform: Platform::MacOS,
+            script_links: Vec::new(),
         }
     }
 
@@ -1,13 +1,215 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::auditable::read_audit_data;
+use super::info::modified_time;
+use super::rustup::PROXIES;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Detector for Cargo installed packages.
-pub struct CargoDetector;
+/// Matches `$CARGO_HOME/bin`; verification reads cargo's install tracking files
+/// (`$CARGO_HOME/.crates2.json`, or the older `.crates.toml`) to map binaries
+/// back to the crates that installed them.
+pub struct CargoDetector {
+    /// `CARGO_HOME`, or `~/.cargo`
+    cargo_home: Option<PathBuf>,
+    installs: OnceCell<Vec<CrateInstall>>,
+}
 
 impl CargoDetector {
     pub fn new() -> Self {
-        Self
+        Self {
+            cargo_home: std::env::var_os("CARGO_HOME")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| dirs::home_dir().map(|home| home.join(".cargo"))),
+            installs: OnceCell::new(),
+        }
+    }
+
+    /// Use the tracking files in `cargo_home` instead of the user's.
+    #[cfg(test)]
+    fn with_cargo_home(cargo_home: &Path) -> Self {
+        Self {
+            cargo_home: Some(cargo_home.to_path_buf()),
+            installs: OnceCell::new(),
+        }
+    }
+
+    fn installs(&self) -> &[CrateInstall] {
+        self.installs.get_or_init(|| match self.cargo_home {
+            Some(ref home) => load_installs(home),
+            None => Vec::new(),
+        })
+    }
+
+    /// The crate that installed `bin_path`, if it is in `$CARGO_HOME/bin`
+    /// (the tracking files only describe that directory).
+    fn find_crate(&self, bin_path: &Path) -> Option<&CrateInstall> {
+        let bin_dir = self.cargo_home.as_ref()?.join("bin");
+        if bin_path.parent() != Some(bin_dir.as_path()) {
+            return None;
+        }
+        let bin = bin_path.file_name()?.to_string_lossy();
+        self.installs()
+            .iter()
+            .find(|install| install.bins.iter().any(|b| *b == bin))
+    }
+
+    /// The command file inside `$CARGO_HOME/bin`, or one matching the
+    /// `.cargo/bin` fragment when `CARGO_HOME` points elsewhere.
+    fn bin_path<'a>(&self, ctx: &'a DetectionContext) -> Option<&'a PathBuf> {
+        let bin = self.cargo_home.as_ref().map(|home| home.join("bin"));
+        ctx.command_files().find(|path| {
+            (bin.is_some() && path.parent() == bin.as_deref()) || is_cargo_bin_path(path)
+        })
+    }
+}
+
+/// Check for Cargo bin paths:
+/// Unix: ~/.cargo/bin/
+/// Windows: %USERPROFILE%\.cargo\bin\
+pub(super) fn is_cargo_bin_path(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    path_str.contains("/.cargo/bin/")
+        || path_str.contains(r"\.cargo\bin\")
+        || path_str.ends_with("/.cargo/bin")
+        || path_str.ends_with(r"\.cargo\bin")
+}
+
+/// One `cargo install`ed crate.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CrateInstall {
+    name: String,
+    version: String,
+    /// Source part of the package id (`registry+https://...`, `git+https://...#rev`, `path+file://...`)
+    source: String,
+    bins: Vec<String>,
+    features: Vec<String>,
+    all_features: bool,
+    no_default_features: bool,
+    profile: Option<String>,
+    target: Option<String>,
+    rustc: Option<String>,
+}
+
+impl CrateInstall {
+    /// Parse a package id: `ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)`.
+    fn from_package_id(id: &str) -> Option<Self> {
+        let (name, rest) = id.split_once(' ')?;
+        let (version, source) = rest.split_once(' ')?;
+        Some(Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            source: source
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Human-readable source: `crates.io`, another registry URL, `git URL#rev` or a local path.
+    fn source_display(&self) -> String {
+        let Some((kind, url)) = self.source.split_once('+') else {
+            return self.source.clone();
+        };
+        match kind {
+            "registry" | "sparse" if is_crates_io(url) => "crates.io".to_string(),
+            "registry" | "sparse" => url.to_string(),
+            "git" => format!("git {url}"),
+            "path" => url.strip_prefix("file://").unwrap_or(url).to_string(),
+            _ => self.source.clone(),
+        }
+    }
+
+    /// Features as passed to `cargo install`.
+    fn features_display(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.all_features {
+            parts.push("all features".to_string());
+        }
+        if self.no_default_features {
+            parts.push("no default features".to_string());
+        }
+        parts.extend(self.features.iter().cloned());
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+fn is_crates_io(url: &str) -> bool {
+    url == "https://github.com/rust-lang/crates.io-index" || url == "https://index.crates.io/"
+}
+
+/// `.crates2.json`: package id -> install details.
+#[derive(Debug, Deserialize)]
+struct Crates2 {
+    installs: BTreeMap<String, Crates2Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Crates2Entry {
+    #[serde(default)]
+    bins: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    all_features: bool,
+    #[serde(default)]
+    no_default_features: bool,
+    profile: Option<String>,
+    target: Option<String>,
+    rustc: Option<String>,
+}
+
+/// Read `.crates2.json`, falling back to `.crates.toml` (which only records bins).
+fn load_installs(cargo_home: &Path) -> Vec<CrateInstall> {
+    if let Some(installs) = std::fs::read_to_string(cargo_home.join(".crates2.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Crates2>(&content).ok())
+    {
+        return installs
+            .installs
+            .into_iter()
+            .filter_map(|(id, entry)| {
+                Some(CrateInstall {
+                    bins: entry.bins,
+                    features: entry.features,
+                    all_features: entry.all_features,
+                    no_default_features: entry.no_default_features,
+                    profile: entry.profile,
+                    target: entry.target,
+                    rustc: entry.rustc,
+                    ..CrateInstall::from_package_id(&id)?
+                })
+            })
+            .collect();
     }
+
+    let Some(v1) = std::fs::read_to_string(cargo_home.join(".crates.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .and_then(|table| table.get("v1")?.as_table().cloned())
+    else {
+        return Vec::new();
+    };
+    v1.iter()
+        .filter_map(|(id, bins)| {
+            Some(CrateInstall {
+                bins: bins
+                    .as_array()?
+                    .iter()
+                    .filter_map(|b| b.as_str().map(str::to_string))
+                    .collect(),
+                ..CrateInstall::from_package_id(id)?
+            })
+        })
+        .collect()
 }
 
 impl PackageManagerDetector for CargoDetector {
@@ -28,30 +230,68 @@ impl PackageManagerDetector for CargoDetector {
     }
 
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
-        for path in &ctx.symlink_chain {
-            let path_str = path.to_string_lossy();
-
-            // Check for Cargo bin paths:
-            // Unix: ~/.cargo/bin/ or $CARGO_HOME/bin/
-            // Windows: %USERPROFILE%\.cargo\bin\ or %CARGO_HOME%\bin\
-            if path_str.contains("/.cargo/bin/")
-                || path_str.contains(r"\.cargo\bin\")
-                || path_str.ends_with("/.cargo/bin")
-                || path_str.ends_with(r"\.cargo\bin")
-            {
-                return Some(DetectionResult {
-                    manager_id: self.id().to_string(),
-                    manager_name: self.name().to_string(),
-                    package_name: Some(ctx.command_name.clone()),
-                    version: None,
-                    confidence: Confidence::Medium,
-                    command_path: ctx.command_path.clone(),
-                    resolved_path: ctx.resolved_path.clone(),
-                });
-            }
+        // rustup's proxies live in the same directory but are not `cargo install` packages
+        let is_rustup = ctx
+            .symlink_chain
+            .first()?
+            .file_stem()
+            .is_some_and(|stem| stem == "rustup" || PROXIES.iter().any(|proxy| stem == *proxy));
+        if is_rustup {
+            return None;
         }
 
-        None
+        self.bin_path(ctx)?;
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            // The crate name comes from the tracking files during verification
+            package_name: Some(ctx.command_name.clone()),
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(install) = self.bin_path(ctx).and_then(|bin| self.find_crate(bin)) else {
+            return Verification::Unverified;
+        };
+        result.package_name = Some(install.name.clone());
+        result.version = Some(install.version.clone());
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let bin_path = self.bin_path(ctx)?;
+        let install = self.find_crate(bin_path)?;
+
+        let mut info = PackageInfo {
+            install_date: modified_time(bin_path),
+            // `cargo install` is always explicit
+            installed_on_request: Some(true),
+            source: Some(install.source_display()),
+            provides: install.bins.clone(),
+            ..Default::default()
+        };
+        let details = [
+            ("features", install.features_display()),
+            ("profile", install.profile.clone()),
+            ("target", install.target.clone()),
+            ("rustc", install.rustc.clone()),
+        ];
+        for (key, value) in details {
+            if let Some(value) = value {
+                info.extra.insert(key.to_string(), value);
+            }
+        }
+        if let Some(audit) = read_audit_data(bin_path) {
+            audit.add_details(&mut info);
+        }
+        Some(info)
     }
 }
 
@@ -69,6 +309,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -117,6 +358,119 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_ignores_rustup_proxies() {
+        let detector = CargoDetector::new();
+        let ctx = make_context(
+            "cargo",
+            vec![
+                "/home/user/.cargo/bin/cargo",
+                "/home/user/.cargo/bin/rustup",
+            ],
+            Platform::Linux,
+        );
+        assert!(detector.detect(&ctx).is_none());
+    }
+
+    fn write_crates2(cargo_home: &Path) {
+        std::fs::create_dir_all(cargo_home.join("bin")).unwrap();
+        std::fs::write(cargo_home.join("bin/rg"), "").unwrap();
+        std::fs::write(
+            cargo_home.join(".crates2.json"),
+            r#"{"installs": {
+                "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)": {
+                    "version_req": null, "bins": ["rg"], "features": ["pcre2"],
+                    "all_features": false, "no_default_features": false,
+                    "profile": "release", "target": "x86_64-unknown-linux-gnu",
+                    "rustc": "rustc 1.75.0 (82e1608df 2023-12-21)"
+                },
+                "why 0.3.0 (git+https://github.com/koizuka/why#3f2a1c0)": {
+                    "bins": ["why"], "profile": "release"
+                }
+            }}"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_reads_crates2_json() {
+        let dir = tempfile::tempdir().unwrap();
+        write_crates2(dir.path());
+        let rg = dir.path().join("bin/rg");
+        let detector = CargoDetector::with_cargo_home(dir.path());
+        let ctx = make_context("rg", vec![rg.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        // Path match only until the tracking file is consulted
+        assert_eq!(result.package_name, Some("rg".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(result.verification, Verification::Unverified);
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("ripgrep".to_string()));
+        assert_eq!(result.version, Some("14.1.0".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.source.as_deref(), Some("crates.io"));
+        assert_eq!(info.provides, vec!["rg"]);
+        assert_eq!(info.extra["features"], "pcre2");
+        assert_eq!(info.extra["profile"], "release");
+    }
+
+    #[test]
+    fn test_falls_back_to_crates_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("bin")).unwrap();
+        std::fs::write(
+            dir.path().join(".crates.toml"),
+            "[v1]\n\"fd-find 9.0.0 (sparse+https://index.crates.io/)\" = [\"fd\"]\n\"tool 0.1.0 (path+file:///home/u/src/tool)\" = [\"tool\"]\n",
+        )
+        .unwrap();
+        let fd = dir.path().join("bin/fd");
+        let detector = CargoDetector::with_cargo_home(dir.path());
+        let ctx = make_context("fd", vec![fd.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("fd-find".to_string()));
+        assert_eq!(result.version, Some("9.0.0".to_string()));
+
+        let tool = dir.path().join("bin/tool");
+        let ctx = make_context("tool", vec![tool.to_str().unwrap()], Platform::Linux);
+        let result = detector.detect(&ctx).unwrap();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.source.as_deref(), Some("/home/u/src/tool"));
+    }
+
+    #[test]
+    fn test_unlisted_binary_in_cargo_home() {
+        let dir = tempfile::tempdir().unwrap();
+        write_crates2(dir.path());
+        let other = dir.path().join("bin/handmade");
+        let detector = CargoDetector::with_cargo_home(dir.path());
+        let ctx = make_context("handmade", vec![other.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+        assert_eq!(result.package_name, Some("handmade".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn test_source_display() {
+        let git = CrateInstall::from_package_id(
+            "why 0.3.0 (git+https://github.com/koizuka/why?branch=main#3f2a1c0)",
+        )
+        .unwrap();
+        assert_eq!(git.name, "why");
+        assert_eq!(
+            git.source_display(),
+            "git https://github.com/koizuka/why?branch=main#3f2a1c0"
+        );
+
+        let registry =
+            CrateInstall::from_package_id("foo 1.0.0 (registry+https://my.registry/index)")
+                .unwrap();
+        assert_eq!(registry.source_display(), "https://my.registry/index");
+    }
+
     #[test]
     fn test_supports_all_platforms() {
         let detector = CargoDetector::new();
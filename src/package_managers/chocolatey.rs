@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for Chocolatey packages (Windows).
@@ -39,7 +39,10 @@ impl PackageManagerDetector for ChocolateyDetector {
                     manager_name: self.name().to_string(),
                     package_name: Some(ctx.command_name.clone()),
                     version: None,
+                    details: Default::default(),
                     confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
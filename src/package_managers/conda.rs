@@ -0,0 +1,377 @@
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Directory names of standalone conda installations (the `base` environment).
+const BASE_INSTALL_NAMES: &[&str] = &[
+    "miniconda3",
+    "miniconda",
+    "anaconda3",
+    "anaconda",
+    "miniforge3",
+    "mambaforge",
+    "micromamba",
+];
+
+/// Detector for conda, mamba and micromamba environments.
+pub struct CondaDetector {
+    /// Parsed `conda-meta` records per environment prefix
+    packages: Mutex<HashMap<PathBuf, Arc<Vec<CondaPackage>>>>,
+}
+
+impl CondaDetector {
+    pub fn new() -> Self {
+        Self {
+            packages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn packages(&self, prefix: &Path) -> Arc<Vec<CondaPackage>> {
+        let mut cache = self.packages.lock().unwrap();
+        cache
+            .entry(prefix.to_path_buf())
+            .or_insert_with(|| Arc::new(load_conda_meta(&prefix.join("conda-meta"))))
+            .clone()
+    }
+
+    /// Find the package whose `files` list contains one of the chain's paths.
+    fn find_owner(&self, env: &CondaEnv, ctx: &DetectionContext) -> Option<CondaPackage> {
+        let packages = self.packages(&env.prefix);
+        ctx.command_files().rev().find_map(|path| {
+            let relative = path.strip_prefix(&env.prefix).ok()?;
+            packages
+                .iter()
+                .find(|p| p.files.iter().any(|f| Path::new(f) == relative))
+                .cloned()
+        })
+    }
+}
+
+/// The environment holding the command, preferring its final target, or
+/// else the one whose Python runs it.
+fn command_env(ctx: &DetectionContext) -> Option<CondaEnv> {
+    ctx.command_files()
+        .rev()
+        .map(PathBuf::as_path)
+        .chain(ctx.interpreter())
+        .find_map(find_env)
+}
+
+/// A conda environment that contains the command.
+#[derive(Debug, Clone, PartialEq)]
+struct CondaEnv {
+    prefix: PathBuf,
+    /// `base` for the root installation, otherwise the `envs/<name>` directory name
+    name: String,
+    /// `conda`, `mamba` or `micromamba`
+    flavor: &'static str,
+}
+
+/// A `conda-meta/<name>-<version>-<build>.json` record.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct CondaPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    build: Option<String>,
+    #[serde(default)]
+    channel: Option<String>,
+    /// Set by conda when the package was asked for explicitly
+    #[serde(default)]
+    requested_spec: Option<String>,
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(skip)]
+    meta_file: PathBuf,
+}
+
+impl CondaPackage {
+    /// Channel name from a channel URL (`https://conda.anaconda.org/conda-forge/linux-64`).
+    fn channel_name(&self) -> Option<String> {
+        let channel = self.channel.as_deref()?;
+        let name = match channel.split_once("://") {
+            Some((_, rest)) => rest.split('/').nth(1).unwrap_or(rest),
+            None => channel,
+        };
+        Some(name.to_string())
+    }
+}
+
+/// Load every package record in a `conda-meta` directory.
+fn load_conda_meta(meta_dir: &Path) -> Vec<CondaPackage> {
+    let Ok(entries) = std::fs::read_dir(meta_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let mut package: CondaPackage = serde_json::from_str(&content).ok()?;
+            package.meta_file = path;
+            Some(package)
+        })
+        .collect()
+}
+
+/// Find the environment prefix for a path: the nearest ancestor with a
+/// `conda-meta` directory, or a recognisable `envs/<env>` / base install path.
+fn find_env(path: &Path) -> Option<CondaEnv> {
+    // The command lives in `bin/` (Unix), `Scripts\`, `Library\bin\` or the prefix itself (Windows)
+    let prefix = path
+        .ancestors()
+        .skip(1)
+        .take(3)
+        .find(|dir| dir.join("conda-meta").is_dir())
+        .map(Path::to_path_buf)
+        .or_else(|| prefix_from_layout(path))?;
+
+    let parent = prefix.parent();
+    let in_envs = parent.is_some_and(|p| p.file_name().is_some_and(|n| n == "envs"));
+    let (name, root) = if in_envs {
+        (
+            prefix.file_name()?.to_string_lossy().to_string(),
+            parent.and_then(Path::parent)?.to_path_buf(),
+        )
+    } else {
+        ("base".to_string(), prefix.clone())
+    };
+
+    Some(CondaEnv {
+        flavor: flavor(&root),
+        name,
+        prefix,
+    })
+}
+
+/// Recognise `.../envs/<env>/bin/<cmd>` and `.../<base install>/bin/<cmd>` without
+/// touching the filesystem.
+fn prefix_from_layout(path: &Path) -> Option<PathBuf> {
+    let bin = path.parent()?;
+    if !bin
+        .file_name()
+        .is_some_and(|n| n == "bin" || n == "Scripts")
+    {
+        return None;
+    }
+    let prefix = bin.parent()?;
+    let is_env = prefix
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|n| n == "envs");
+    let is_base = prefix
+        .file_name()
+        .is_some_and(|n| BASE_INSTALL_NAMES.iter().any(|b| n == *b));
+    (is_env || is_base).then(|| prefix.to_path_buf())
+}
+
+/// Which tool manages an installation root.
+fn flavor(root: &Path) -> &'static str {
+    let root_str = root.to_string_lossy();
+    if root_str.contains("micromamba") {
+        "micromamba"
+    } else if root.join("bin/mamba").exists() || root.join(r"Scripts\mamba.exe").exists() {
+        "mamba"
+    } else {
+        "conda"
+    }
+}
+
+impl PackageManagerDetector for CondaDetector {
+    fn id(&self) -> &'static str {
+        "conda"
+    }
+
+    fn name(&self) -> &str {
+        "conda"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true // conda is cross-platform
+    }
+
+    fn priority(&self) -> i32 {
+        85
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let env = command_env(ctx)?;
+
+        // The owning package comes from `conda-meta` during verification
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: format!("{} ({})", env.flavor, env.name),
+            package_name: None,
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(package) = command_env(ctx).and_then(|env| self.find_owner(&env, ctx)) else {
+            return Verification::Unverified;
+        };
+        result.package_name = Some(package.name);
+        result.version = Some(package.version);
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let env = command_env(ctx)?;
+        let package = self.find_owner(&env, ctx)?;
+
+        let mut provides: Vec<String> = package
+            .files
+            .iter()
+            .filter_map(|f| {
+                f.strip_prefix("bin/")
+                    .or_else(|| f.strip_prefix("Scripts/"))
+            })
+            .filter(|name| !name.contains('/'))
+            .map(str::to_string)
+            .collect();
+        provides.sort();
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&package.meta_file),
+            installed_on_request: package.requested_spec.as_ref().map(|s| !s.is_empty()),
+            source: package.channel_name(),
+            provides,
+            ..Default::default()
+        };
+        info.extra.insert("environment".to_string(), env.name);
+        info.extra
+            .insert("prefix".to_string(), env.prefix.display().to_string());
+        if let Some(build) = package.build {
+            info.extra.insert("build".to_string(), build);
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&Path>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn write_meta(prefix: &Path, file: &str, json: &str) {
+        let meta = prefix.join("conda-meta");
+        std::fs::create_dir_all(&meta).unwrap();
+        std::fs::write(meta.join(file), json).unwrap();
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let env = dir.path().join("miniforge3/envs/ml");
+        std::fs::create_dir_all(env.join("bin")).unwrap();
+        std::fs::create_dir_all(dir.path().join("miniforge3/bin")).unwrap();
+        std::fs::write(dir.path().join("miniforge3/bin/mamba"), "").unwrap();
+        write_meta(
+            &env,
+            "jupyter_core-5.7.1-py311h38be061_0.json",
+            r#"{
+                "name": "jupyter_core",
+                "version": "5.7.1",
+                "build": "py311h38be061_0",
+                "channel": "https://conda.anaconda.org/conda-forge/linux-64",
+                "requested_spec": "jupyter_core",
+                "files": ["bin/jupyter", "bin/jupyter-migrate", "lib/python3.11/site-packages/jupyter.py"]
+            }"#,
+        );
+        write_meta(
+            &env,
+            "python-3.11.7-hab00c5b_1.json",
+            r#"{"name": "python", "version": "3.11.7", "channel": "conda-forge", "requested_spec": "", "files": ["bin/python3.11", "bin/python"]}"#,
+        );
+        dir
+    }
+
+    #[test]
+    fn test_detects_env_package() {
+        let root = fixture();
+        let jupyter = root.path().join("miniforge3/envs/ml/bin/jupyter");
+        let detector = CondaDetector::new();
+        let ctx = make_context("jupyter", vec![&jupyter]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "conda");
+        assert_eq!(result.manager_name, "mamba (ml)");
+        assert_eq!(result.package_name, None);
+        assert_eq!(result.confidence, Confidence::Medium);
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("jupyter_core".to_string()));
+        assert_eq!(result.version, Some("5.7.1".to_string()));
+    }
+
+    #[test]
+    fn test_unlisted_file_is_medium() {
+        let root = fixture();
+        let tool = root.path().join("miniforge3/envs/ml/bin/handmade");
+        let detector = CondaDetector::new();
+        let ctx = make_context("handmade", vec![&tool]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+        assert_eq!(result.package_name, None);
+        assert_eq!(result.confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn test_info() {
+        let root = fixture();
+        let python = root.path().join("miniforge3/envs/ml/bin/python");
+        let detector = CondaDetector::new();
+        let ctx = make_context("python", vec![&python]);
+        let result = detector.detect(&ctx).unwrap();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.source.as_deref(), Some("conda-forge"));
+        assert_eq!(info.installed_on_request, Some(false));
+        assert_eq!(info.provides, vec!["python", "python3.11"]);
+        assert_eq!(info.extra["environment"], "ml");
+    }
+
+    #[test]
+    fn test_channel_name() {
+        let package = CondaPackage {
+            channel: Some("https://conda.anaconda.org/conda-forge/linux-64".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(package.channel_name().as_deref(), Some("conda-forge"));
+    }
+
+    #[test]
+    fn test_layout_without_metadata() {
+        let env = find_env(Path::new("/home/user/micromamba/envs/dev/bin/ipython")).unwrap();
+        assert_eq!(env.name, "dev");
+        assert_eq!(env.flavor, "micromamba");
+
+        let base = find_env(Path::new("/opt/anaconda3/bin/conda")).unwrap();
+        assert_eq!(base.name, "base");
+        assert_eq!(base.flavor, "conda");
+
+        assert!(find_env(Path::new("/usr/bin/python3")).is_none());
+    }
+}
@@ -0,0 +1,297 @@
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
+use crate::error::{Result, WhyError};
+use crate::platform::Platform;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Priority used when a rule does not specify one (same level as apt).
+const DEFAULT_PRIORITY: i32 = 50;
+
+/// Top-level layout of a custom rules file (`--database`).
+///
+/// ```toml
+/// [[detectors]]
+/// id = "corp-tools"
+/// name = "Corp Tools"
+/// platforms = ["linux", "macos"]
+/// priority = 110
+/// patterns = ['/opt/corp/tools/(?P<package>[^/]+)/(?P<version>[^/]+)/bin/']
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    detectors: Vec<Rule>,
+}
+
+/// A single declarative detector definition.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    id: String,
+    name: String,
+    /// Platforms the rule applies to (all platforms when omitted)
+    #[serde(default)]
+    platforms: Option<Vec<Platform>>,
+    #[serde(default = "default_priority")]
+    priority: i32,
+    /// Regexes matched against every path in the symlink chain, with optional
+    /// `package` and `version` named captures
+    patterns: Vec<String>,
+}
+
+fn default_priority() -> i32 {
+    DEFAULT_PRIORITY
+}
+
+/// Detector built from a rule in a custom rules file.
+pub struct CustomDetector {
+    id: String,
+    name: String,
+    platforms: Option<Vec<Platform>>,
+    priority: i32,
+    patterns: Vec<Regex>,
+}
+
+/// Load detectors from a TOML (or `.json`) rules file.
+/// `reserved` lists the ids already taken by built-in detectors; verify and
+/// info are dispatched by id, so a rule may not reuse one (or another rule's).
+pub fn load_rules(path: &Path, reserved: &[&str]) -> Result<Vec<CustomDetector>> {
+    let content = std::fs::read_to_string(path).map_err(|e| database_error(path, e))?;
+
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let file: RulesFile = if is_json {
+        serde_json::from_str(&content).map_err(|e| database_error(path, e))?
+    } else {
+        toml::from_str(&content).map_err(|e| database_error(path, e))?
+    };
+
+    let mut seen = HashSet::new();
+    file.detectors
+        .into_iter()
+        .map(|rule| {
+            if reserved.contains(&rule.id.as_str()) {
+                return Err(format!(
+                    "detector '{}' uses the id of a built-in detector",
+                    rule.id
+                ));
+            }
+            if !seen.insert(rule.id.clone()) {
+                return Err(format!(
+                    "detector id '{}' is defined more than once",
+                    rule.id
+                ));
+            }
+            CustomDetector::from_rule(rule)
+        })
+        .map(|detector| detector.map_err(|msg| database_error(path, msg)))
+        .collect()
+}
+
+fn database_error(path: &Path, message: impl ToString) -> WhyError {
+    WhyError::DatabaseError {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    }
+}
+
+impl CustomDetector {
+    fn from_rule(rule: Rule) -> std::result::Result<Self, String> {
+        if rule.id.trim().is_empty() {
+            return Err("detector id must not be empty".to_string());
+        }
+        if rule.patterns.is_empty() {
+            return Err(format!("detector '{}' has no patterns", rule.id));
+        }
+
+        let patterns = rule
+            .patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("detector '{}': {}", rule.id, e)))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            id: rule.id,
+            name: rule.name,
+            platforms: rule.platforms,
+            priority: rule.priority,
+            patterns,
+        })
+    }
+}
+
+impl PackageManagerDetector for CustomDetector {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        self.platforms
+            .as_ref()
+            .is_none_or(|platforms| platforms.contains(&platform))
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        for path in &ctx.symlink_chain {
+            let path_str = path.to_string_lossy();
+
+            for pattern in &self.patterns {
+                if let Some(captures) = pattern.captures(&path_str) {
+                    let capture = |name| captures.name(name).map(|m| m.as_str().to_string());
+
+                    return Some(DetectionResult {
+                        manager_id: self.id.clone(),
+                        manager_name: self.name.clone(),
+                        package_name: capture("package"),
+                        version: capture("version"),
+                        details: Default::default(),
+                        confidence: Confidence::Medium,
+                        verification: Verification::Unverified,
+                        info: None,
+                        command_path: ctx.command_path.clone(),
+                        resolved_path: ctx.resolved_path.clone(),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_context(command: &str, paths: Vec<&str>, platform: Platform) -> DetectionContext {
+        let command_path = PathBuf::from(paths.first().unwrap_or(&""));
+        let resolved_path = PathBuf::from(paths.last().unwrap_or(&""));
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: command_path.clone(),
+            symlink_chain: paths.iter().map(PathBuf::from).collect(),
+            resolved_path,
+            platform,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn write_rules(name: &str, content: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    const CORP_RULES: &str = r#"
+[[detectors]]
+id = "corp-tools"
+name = "Corp Tools"
+platforms = ["linux", "macos"]
+priority = 110
+patterns = ['^/opt/corp/tools/(?P<package>[^/]+)/(?P<version>[^/]+)/bin/']
+"#;
+
+    #[test]
+    fn test_loads_toml_rules() {
+        let (_dir, path) = write_rules("rules.toml", CORP_RULES);
+        let detectors = load_rules(&path, &[]).unwrap();
+        assert_eq!(detectors.len(), 1);
+
+        let detector = &detectors[0];
+        assert_eq!(detector.id(), "corp-tools");
+        assert_eq!(detector.priority(), 110);
+        assert!(detector.supports_platform(Platform::Linux));
+        assert!(!detector.supports_platform(Platform::Windows));
+
+        let ctx = make_context(
+            "deploy",
+            vec![
+                "/usr/local/bin/deploy",
+                "/opt/corp/tools/deployer/2.3.1/bin/deploy",
+            ],
+            Platform::Linux,
+        );
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "corp-tools");
+        assert_eq!(result.manager_name, "Corp Tools");
+        assert_eq!(result.package_name, Some("deployer".to_string()));
+        assert_eq!(result.version, Some("2.3.1".to_string()));
+    }
+
+    #[test]
+    fn test_loads_json_rules() {
+        let (_dir, path) = write_rules(
+            "rules.json",
+            r#"{"detectors": [{"id": "corp", "name": "Corp", "patterns": ["/opt/corp/bin/"]}]}"#,
+        );
+        let detectors = load_rules(&path, &[]).unwrap();
+        assert_eq!(detectors[0].priority(), DEFAULT_PRIORITY);
+        assert!(detectors[0].supports_platform(Platform::Windows));
+
+        let ctx = make_context("tool", vec!["/opt/corp/bin/tool"], Platform::Linux);
+        let result = detectors[0].detect(&ctx).unwrap();
+        assert_eq!(result.package_name, None);
+    }
+
+    #[test]
+    fn test_ignores_non_matching_paths() {
+        let (_dir, path) = write_rules("rules.toml", CORP_RULES);
+        let detectors = load_rules(&path, &[]).unwrap();
+        let ctx = make_context("git", vec!["/usr/bin/git"], Platform::Linux);
+        assert!(detectors[0].detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_rejects_invalid_regex() {
+        let (_dir, path) = write_rules(
+            "rules.toml",
+            "[[detectors]]\nid = \"bad\"\nname = \"Bad\"\npatterns = ['(unclosed']\n",
+        );
+        let err = load_rules(&path, &[]).err().unwrap();
+        assert!(err.to_string().contains("bad"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let (_dir, path) = write_rules(
+            "rules.toml",
+            "[[detectors]]\nid = \"x\"\nname = \"X\"\npattern = ['/x/']\n",
+        );
+        assert!(load_rules(&path, &[]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_and_builtin_ids() {
+        let (_dir, path) = write_rules(
+            "rules.toml",
+            &format!("{CORP_RULES}{}", CORP_RULES.replace("110", "120")),
+        );
+        let err = load_rules(&path, &[]).err().unwrap().to_string();
+        assert!(err.contains("rules.toml"));
+        assert!(err.contains("'corp-tools' is defined more than once"));
+
+        let (_dir, path) = write_rules(
+            "rules.toml",
+            "[[detectors]]\nid = \"homebrew\"\nname = \"My Brew\"\npatterns = ['/x/']\n",
+        );
+        let err = load_rules(&path, &["homebrew", "apt"])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("'homebrew' uses the id of a built-in detector"));
+    }
+}
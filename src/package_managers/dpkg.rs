@@ -0,0 +1,386 @@
+//! Native reader for the dpkg database (`/var/lib/dpkg`), so the apt
+//! detector works without spawning `dpkg` (or in containers without it).
+
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Top-level directories that are symlinks into `/usr` on usrmerge systems.
+const USRMERGE_DIRS: &[&str] = &["bin", "sbin", "lib", "lib32", "lib64", "libx32"];
+
+/// An installed package as recorded in `/var/lib/dpkg/status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DpkgPackage {
+    pub name: String,
+    pub version: String,
+    pub architecture: String,
+    /// e.g. `install ok installed`
+    pub status: String,
+    /// First line of the `Description` field
+    pub summary: Option<String>,
+    pub homepage: Option<String>,
+}
+
+impl DpkgPackage {
+    pub fn is_installed(&self) -> bool {
+        self.status.ends_with(" installed")
+    }
+}
+
+/// A `dpkg-divert` entry: `from` was moved to `to` by `package` (`None` for local).
+#[derive(Debug, Clone)]
+struct Diversion {
+    from: PathBuf,
+    to: PathBuf,
+    package: Option<String>,
+}
+
+/// Lazily-loaded view of a dpkg admin directory.
+pub(super) struct DpkgDatabase {
+    admin_dir: PathBuf,
+    /// File path -> owning `.list` names (`pkg` or `pkg:arch`)
+    files: OnceCell<HashMap<PathBuf, Vec<String>>>,
+    packages: OnceCell<Vec<DpkgPackage>>,
+    diversions: OnceCell<Vec<Diversion>>,
+}
+
+impl DpkgDatabase {
+    /// Open the dpkg database below `root` (`/` for the running system).
+    pub fn new(root: &Path) -> Self {
+        Self {
+            admin_dir: root.join("var/lib/dpkg"),
+            files: OnceCell::new(),
+            packages: OnceCell::new(),
+            diversions: OnceCell::new(),
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.admin_dir.join("status").is_file()
+    }
+
+    /// Find the installed package that owns `path`, accounting for usrmerge
+    /// aliases (`/bin/ls` vs `/usr/bin/ls`) and `dpkg-divert` diversions.
+    pub fn find_owner(&self, path: &Path) -> Option<DpkgPackage> {
+        usrmerge_aliases(path)
+            .iter()
+            .find_map(|candidate| self.owner_of(candidate))
+    }
+
+    fn owner_of(&self, path: &Path) -> Option<DpkgPackage> {
+        let diversions = self.diversions();
+
+        // The file now at a diversion target is the original package's file
+        if let Some(diversion) = diversions.iter().find(|d| d.to == path) {
+            return self.listed_owners(&diversion.from).find_map(|owner| {
+                let package = self.package_for_list(&owner)?;
+                (Some(&package.name) != diversion.package.as_ref()).then_some(package)
+            });
+        }
+
+        // A diverted path belongs to the package that diverted it
+        if let Some(diversion) = diversions.iter().find(|d| d.from == path) {
+            return match &diversion.package {
+                Some(diverter) => self.package(diverter, None),
+                None => None, // Local diversion: the admin put the file there
+            };
+        }
+
+        self.listed_owners(path)
+            .find_map(|owner| self.package_for_list(&owner))
+    }
+
+    fn listed_owners(&self, path: &Path) -> impl Iterator<Item = String> + '_ {
+        self.files()
+            .get(path)
+            .into_iter()
+            .flat_map(|owners| owners.iter().cloned())
+    }
+
+    /// Look up an installed package by name (and architecture, if known).
+    pub fn package(&self, name: &str, architecture: Option<&str>) -> Option<DpkgPackage> {
+        self.packages()
+            .iter()
+            .find(|p| {
+                p.name == name
+                    && p.is_installed()
+                    && architecture.is_none_or(|arch| p.architecture == arch)
+            })
+            .cloned()
+    }
+
+    /// Paths listed in a package's `.list` file. Multi-arch packages have one
+    /// list per architecture (`libfoo:amd64.list`); only this one's is read.
+    pub fn package_files(&self, package: &DpkgPackage) -> Vec<PathBuf> {
+        let qualified = format!("{}:{}", package.name, package.architecture);
+        let mut files: Vec<PathBuf> = self
+            .files()
+            .iter()
+            .filter(|(_, owners)| owners.iter().any(|o| *o == package.name || *o == qualified))
+            .map(|(path, _)| path.clone())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// The `.list` file of a package, used for its install time.
+    pub fn list_file(&self, package: &DpkgPackage) -> Option<PathBuf> {
+        let info_dir = self.admin_dir.join("info");
+        [
+            format!("{}.list", package.name),
+            format!("{}:{}.list", package.name, package.architecture),
+        ]
+        .into_iter()
+        .map(|name| info_dir.join(name))
+        .find(|path| path.is_file())
+    }
+
+    fn package_for_list(&self, list_name: &str) -> Option<DpkgPackage> {
+        match list_name.split_once(':') {
+            Some((name, arch)) => self.package(name, Some(arch)),
+            None => self.package(list_name, None),
+        }
+    }
+
+    fn files(&self) -> &HashMap<PathBuf, Vec<String>> {
+        self.files
+            .get_or_init(|| load_file_index(&self.admin_dir.join("info")))
+    }
+
+    fn packages(&self) -> &[DpkgPackage] {
+        self.packages.get_or_init(|| {
+            std::fs::read_to_string(self.admin_dir.join("status"))
+                .map(|content| parse_status(&content))
+                .unwrap_or_default()
+        })
+    }
+
+    fn diversions(&self) -> &[Diversion] {
+        self.diversions.get_or_init(|| {
+            std::fs::read_to_string(self.admin_dir.join("diversions"))
+                .map(|content| parse_diversions(&content))
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Build the file -> package index from every `info/*.list` file.
+fn load_file_index(info_dir: &Path) -> HashMap<PathBuf, Vec<String>> {
+    let mut index: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(info_dir) else {
+        return index;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(owner) = file_name.strip_suffix(".list") else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        for line in content.lines().filter(|l| !l.is_empty() && *l != "/.") {
+            index
+                .entry(PathBuf::from(line))
+                .or_default()
+                .push(owner.to_string());
+        }
+    }
+    index
+}
+
+/// Parse the deb822 stanzas of `/var/lib/dpkg/status`.
+fn parse_status(content: &str) -> Vec<DpkgPackage> {
+    content
+        .split("\n\n")
+        .filter_map(|stanza| {
+            let mut fields: HashMap<&str, &str> = HashMap::new();
+            for line in stanza.lines() {
+                // Continuation lines (e.g. the long description) start with a space
+                if line.starts_with([' ', '\t']) {
+                    continue;
+                }
+                if let Some((key, value)) = line.split_once(':') {
+                    fields.insert(key, value.trim());
+                }
+            }
+            let field = |key: &str| fields.get(key).map(|v| v.to_string());
+
+            Some(DpkgPackage {
+                name: field("Package")?,
+                version: field("Version").unwrap_or_default(),
+                architecture: field("Architecture").unwrap_or_default(),
+                status: field("Status").unwrap_or_default(),
+                summary: field("Description").filter(|d| !d.is_empty()),
+                homepage: field("Homepage"),
+            })
+        })
+        .collect()
+}
+
+/// `/var/lib/dpkg/diversions` holds three lines per diversion:
+/// original path, diverted-to path, diverting package (`:` for local).
+fn parse_diversions(content: &str) -> Vec<Diversion> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .chunks_exact(3)
+        .map(|chunk| Diversion {
+            from: PathBuf::from(chunk[0]),
+            to: PathBuf::from(chunk[1]),
+            package: (chunk[2] != ":").then(|| chunk[2].to_string()),
+        })
+        .collect()
+}
+
+/// The path itself plus its usrmerge alias (`/usr/bin/x` <-> `/bin/x`).
+fn usrmerge_aliases(path: &Path) -> Vec<PathBuf> {
+    let mut aliases = vec![path.to_path_buf()];
+    if let Ok(rest) = path.strip_prefix("/usr") {
+        if rest
+            .iter()
+            .next()
+            .is_some_and(|dir| USRMERGE_DIRS.iter().any(|d| dir == *d))
+        {
+            aliases.push(Path::new("/").join(rest));
+        }
+    } else if let Ok(rest) = path.strip_prefix("/") {
+        if rest
+            .iter()
+            .next()
+            .is_some_and(|dir| USRMERGE_DIRS.iter().any(|d| dir == *d))
+        {
+            aliases.push(Path::new("/usr").join(rest));
+        }
+    }
+    aliases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "\
+Package: coreutils
+Status: install ok installed
+Architecture: amd64
+Version: 9.1-1
+Description: GNU core utilities
+ This package contains the basic file, shell and text manipulation
+ utilities which are expected to exist on every operating system.
+Homepage: https://www.gnu.org/software/coreutils
+
+Package: libc-bin
+Status: install ok installed
+Architecture: amd64
+Version: 2.36-9
+Description: GNU C Library: Binaries
+
+Package: dash
+Status: install ok installed
+Architecture: amd64
+Version: 0.5.12-2
+Description: POSIX-compliant shell
+
+Package: bash
+Status: install ok installed
+Architecture: amd64
+Version: 5.2.15-2
+Description: GNU Bourne Again SHell
+
+Package: oldtool
+Status: deinstall ok config-files
+Architecture: amd64
+Version: 1.0
+Description: Removed package
+";
+
+    fn fixture() -> (tempfile::TempDir, DpkgDatabase) {
+        let dir = tempfile::tempdir().unwrap();
+        let admin = dir.path().join("var/lib/dpkg");
+        let info = admin.join("info");
+        std::fs::create_dir_all(&info).unwrap();
+        std::fs::write(admin.join("status"), STATUS).unwrap();
+        std::fs::write(info.join("coreutils.list"), "/.\n/bin\n/bin/ls\n/bin/cat\n").unwrap();
+        std::fs::write(info.join("libc-bin.list"), "/.\n/usr/bin/ldd\n").unwrap();
+        std::fs::write(info.join("dash.list"), "/.\n/bin/dash\n/bin/sh\n").unwrap();
+        std::fs::write(info.join("bash.list"), "/.\n/bin/bash\n/bin/sh\n").unwrap();
+        std::fs::write(info.join("oldtool.list"), "/.\n/usr/bin/oldtool\n").unwrap();
+        std::fs::write(
+            admin.join("diversions"),
+            "/bin/sh\n/bin/sh.distrib\ndash\n/usr/bin/local-tool\n/usr/bin/local-tool.orig\n:\n",
+        )
+        .unwrap();
+        let db = DpkgDatabase::new(dir.path());
+        (dir, db)
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let packages = parse_status(STATUS);
+        assert_eq!(packages.len(), 5);
+        assert_eq!(packages[0].name, "coreutils");
+        assert_eq!(packages[0].version, "9.1-1");
+        assert_eq!(packages[0].architecture, "amd64");
+        assert_eq!(packages[0].summary.as_deref(), Some("GNU core utilities"));
+        assert!(packages[0].is_installed());
+        assert!(!packages[4].is_installed());
+    }
+
+    #[test]
+    fn test_find_owner_direct() {
+        let (_dir, db) = fixture();
+        let owner = db.find_owner(Path::new("/usr/bin/ldd")).unwrap();
+        assert_eq!(owner.name, "libc-bin");
+        assert_eq!(owner.version, "2.36-9");
+    }
+
+    #[test]
+    fn test_find_owner_usrmerge() {
+        let (_dir, db) = fixture();
+        // coreutils lists /bin/ls, but the resolved path on usrmerge is /usr/bin/ls
+        let owner = db.find_owner(Path::new("/usr/bin/ls")).unwrap();
+        assert_eq!(owner.name, "coreutils");
+    }
+
+    #[test]
+    fn test_find_owner_diverted() {
+        let (_dir, db) = fixture();
+        // dash diverted bash's /bin/sh to /bin/sh.distrib and ships its own /bin/sh
+        assert_eq!(db.find_owner(Path::new("/bin/sh")).unwrap().name, "dash");
+        assert_eq!(
+            db.find_owner(Path::new("/usr/bin/sh.distrib"))
+                .unwrap()
+                .name,
+            "bash"
+        );
+        // Local diversions are not owned by any package
+        assert!(db.find_owner(Path::new("/usr/bin/local-tool")).is_none());
+    }
+
+    #[test]
+    fn test_removed_package_is_not_owner() {
+        let (_dir, db) = fixture();
+        assert!(db.find_owner(Path::new("/usr/bin/oldtool")).is_none());
+    }
+
+    #[test]
+    fn test_package_files() {
+        let (_dir, db) = fixture();
+        assert_eq!(
+            db.package_files(&db.package("coreutils", None).unwrap()),
+            vec![
+                PathBuf::from("/bin"),
+                PathBuf::from("/bin/cat"),
+                PathBuf::from("/bin/ls")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DpkgDatabase::new(dir.path());
+        assert!(!db.exists());
+        assert!(db.find_owner(Path::new("/usr/bin/ls")).is_none());
+    }
+}
@@ -0,0 +1,310 @@
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// A deployed app: `{installation}/app/{id}/{arch}/{branch}/{commit}/export/bin/{command}`
+static DEPLOY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?P<installation>.+/flatpak)/app/(?P<id>[^/]+)/(?P<arch>[^/]+)/(?P<branch>[^/]+)/(?P<commit>[^/]+)/export/bin/[^/]+$",
+    )
+    .unwrap()
+});
+
+/// An exported launcher: `{installation}/exports/bin/{id}`
+static EXPORT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?P<installation>.+/flatpak)/exports/bin/(?P<id>[^/]+)$").unwrap());
+
+/// Detector for Flatpak applications (system and per-user installations).
+pub struct FlatpakDetector;
+
+impl FlatpakDetector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A Flatpak deployment identified from the symlink chain.
+#[derive(Debug, Clone, PartialEq)]
+struct Deployment {
+    installation: PathBuf,
+    id: String,
+    arch: String,
+    branch: String,
+    commit: String,
+}
+
+impl Deployment {
+    fn dir(&self) -> PathBuf {
+        self.installation
+            .join("app")
+            .join(&self.id)
+            .join(&self.arch)
+            .join(&self.branch)
+            .join(&self.commit)
+    }
+
+    /// `user` for `~/.local/share/flatpak`, `system` otherwise.
+    fn scope(&self) -> &'static str {
+        if self.installation.ends_with(".local/share/flatpak") {
+            "user"
+        } else {
+            "system"
+        }
+    }
+}
+
+impl PackageManagerDetector for FlatpakDetector {
+    fn id(&self) -> &'static str {
+        "flatpak"
+    }
+
+    fn name(&self) -> &str {
+        "Flatpak"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        platform == Platform::Linux
+    }
+
+    fn priority(&self) -> i32 {
+        80
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let (package_name, version) = match find_deployment(&ctx.symlink_chain) {
+            Some(deployment) => (deployment.id, Some(deployment.branch)),
+            // A launcher whose deployment could not be resolved
+            None => {
+                let id = ctx.symlink_chain.iter().find_map(|p| {
+                    EXPORT_REGEX
+                        .captures(&p.to_string_lossy())
+                        .map(|c| c["id"].to_string())
+                })?;
+                (id, None)
+            }
+        };
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(package_name),
+            version,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, _result: &mut DetectionResult) -> Verification {
+        let Some(deployment) = find_deployment(&ctx.symlink_chain) else {
+            return Verification::Unverified;
+        };
+        let Ok(content) = std::fs::read_to_string(deployment.dir().join("metadata")) else {
+            return Verification::Unverified;
+        };
+
+        if metadata_value(&content, "Application", "name").as_deref() != Some(&deployment.id) {
+            return Verification::Contradicted;
+        }
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let deployment = find_deployment(&ctx.symlink_chain)?;
+        let dir = deployment.dir();
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&dir),
+            installed_on_request: Some(true),
+            provides: vec![deployment.id.clone()],
+            ..Default::default()
+        };
+        info.extra
+            .insert("installation".to_string(), deployment.scope().to_string());
+        info.extra
+            .insert("arch".to_string(), deployment.arch.clone());
+        info.extra
+            .insert("branch".to_string(), deployment.branch.clone());
+        info.extra
+            .insert("commit".to_string(), deployment.commit.clone());
+
+        if let Ok(content) = std::fs::read_to_string(dir.join("metadata")) {
+            if let Some(runtime) = metadata_value(&content, "Application", "runtime") {
+                info.extra.insert("runtime".to_string(), runtime);
+            }
+        }
+        Some(info)
+    }
+}
+
+/// Find the deployment from the chain. Launchers usually resolve straight
+/// into the deployment; otherwise the wrapper's `current/active` links are followed.
+fn find_deployment(chain: &[PathBuf]) -> Option<Deployment> {
+    chain.iter().rev().find_map(|path| {
+        let path_str = path.to_string_lossy();
+        if let Some(captures) = DEPLOY_REGEX.captures(&path_str) {
+            // `current` and `active` are symlinks to the arch/branch and commit
+            if &captures["arch"] != "current" {
+                return Some(Deployment {
+                    installation: PathBuf::from(&captures["installation"]),
+                    id: captures["id"].to_string(),
+                    arch: captures["arch"].to_string(),
+                    branch: captures["branch"].to_string(),
+                    commit: captures["commit"].to_string(),
+                });
+            }
+        }
+
+        let captures = EXPORT_REGEX
+            .captures(&path_str)
+            .or_else(|| DEPLOY_REGEX.captures(&path_str))?;
+        resolve_active(Path::new(&captures["installation"]), &captures["id"])
+    })
+}
+
+/// Resolve `app/{id}/current/active` to the deployed arch, branch and commit.
+fn resolve_active(installation: &Path, id: &str) -> Option<Deployment> {
+    let app_dir = installation.join("app").join(id);
+    let active = app_dir.join("current/active").canonicalize().ok()?;
+    let relative = active.strip_prefix(app_dir.canonicalize().ok()?).ok()?;
+
+    let mut parts = relative.iter().map(|p| p.to_string_lossy().to_string());
+    Some(Deployment {
+        installation: installation.to_path_buf(),
+        id: id.to_string(),
+        arch: parts.next()?,
+        branch: parts.next()?,
+        commit: parts.next()?,
+    })
+}
+
+/// Read `key` from `[section]` of an INI-style Flatpak `metadata` file.
+fn metadata_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+        } else if in_section {
+            if let Some((k, v)) = line.split_once('=') {
+                if k.trim() == key {
+                    return Some(v.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&str>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    const COMMIT: &str = "3f1c0ec2ad6ef3cc0b4d1b2a8b4ec4a51d6e2c1fb0b1d5a9e6b2a7c3d4e5f6a7";
+
+    #[test]
+    fn test_detects_system_deployment() {
+        let detector = FlatpakDetector::new();
+        let deployed = format!(
+            "/var/lib/flatpak/app/org.gimp.GIMP/x86_64/stable/{COMMIT}/export/bin/org.gimp.GIMP"
+        );
+        let ctx = make_context(
+            "org.gimp.GIMP",
+            vec!["/var/lib/flatpak/exports/bin/org.gimp.GIMP", &deployed],
+        );
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "flatpak");
+        assert_eq!(result.package_name, Some("org.gimp.GIMP".to_string()));
+        assert_eq!(result.version, Some("stable".to_string()));
+
+        let deployment = find_deployment(&ctx.symlink_chain).unwrap();
+        assert_eq!(deployment.arch, "x86_64");
+        assert_eq!(deployment.commit, COMMIT);
+        assert_eq!(deployment.scope(), "system");
+    }
+
+    #[test]
+    fn test_detects_unresolved_export() {
+        let detector = FlatpakDetector::new();
+        let ctx = make_context(
+            "org.mozilla.firefox",
+            vec!["/home/user/.local/share/flatpak/exports/bin/org.mozilla.firefox"],
+        );
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("org.mozilla.firefox".to_string()));
+        assert_eq!(result.version, None);
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let detector = FlatpakDetector::new();
+        let ctx = make_context("ls", vec!["/usr/bin/ls"]);
+        assert!(detector.detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_metadata_value() {
+        let metadata = "[Application]\nname=org.gimp.GIMP\nruntime=org.gnome.Platform/x86_64/45\n\n[Context]\nshared=network;ipc;\n";
+        assert_eq!(
+            metadata_value(metadata, "Application", "runtime").as_deref(),
+            Some("org.gnome.Platform/x86_64/45")
+        );
+        assert_eq!(metadata_value(metadata, "Context", "runtime"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_user_installation_with_metadata() {
+        let home = tempfile::tempdir().unwrap();
+        let installation = home.path().join(".local/share/flatpak");
+        let app_dir = installation.join("app/org.example.App");
+        let deploy = app_dir.join("x86_64/beta").join(COMMIT);
+        std::fs::create_dir_all(deploy.join("export/bin")).unwrap();
+        std::fs::write(
+            deploy.join("metadata"),
+            "[Application]\nname=org.example.App\nruntime=org.freedesktop.Platform/x86_64/23.08\n",
+        )
+        .unwrap();
+        std::fs::write(deploy.join("export/bin/org.example.App"), "#!/bin/sh\n").unwrap();
+        std::os::unix::fs::symlink("x86_64/beta", app_dir.join("current")).unwrap();
+        std::os::unix::fs::symlink(COMMIT, app_dir.join("x86_64/beta/active")).unwrap();
+
+        // Only the launcher is known; the deployment is found via current/active
+        let export = installation.join("exports/bin/org.example.App");
+        let ctx = make_context("org.example.App", vec![export.to_str().unwrap()]);
+
+        let detector = FlatpakDetector::new();
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("beta".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.extra["installation"], "user");
+        assert_eq!(info.extra["commit"], COMMIT);
+        assert_eq!(
+            info.extra["runtime"],
+            "org.freedesktop.Platform/x86_64/23.08"
+        );
+    }
+}
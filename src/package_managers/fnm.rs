@@ -0,0 +1,207 @@
+use super::node_global::{
+    node_install_info, node_install_result, verify_node_install, NodeInstall,
+};
+use super::{DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector, Verification};
+use crate::platform::Platform;
+use std::path::{Path, PathBuf};
+
+/// Detector for fnm (Fast Node Manager) installed Node.js.
+/// fnm installs each version to `<fnm dir>/node-versions/v<version>/installation`
+/// and puts per-shell symlinks to it under `fnm_multishells/` on `PATH`.
+pub struct FnmDetector {
+    /// `FNM_DIR`, `~/.fnm` if present, or `<data dir>/fnm`
+    fnm_dir: Option<PathBuf>,
+}
+
+impl FnmDetector {
+    pub fn new() -> Self {
+        let fnm_dir = std::env::var_os("FNM_DIR")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                dirs::home_dir()
+                    .map(|home| home.join(".fnm"))
+                    .filter(|legacy| legacy.is_dir())
+            })
+            .or_else(|| dirs::data_dir().map(|data| data.join("fnm")));
+        Self { fnm_dir }
+    }
+
+    /// The installation a path belongs to, directly or through a multishell
+    /// or alias symlink. Returns the install and the real path of the command.
+    fn find_install(&self, ctx: &DetectionContext) -> Option<(NodeInstall, PathBuf)> {
+        let fnm_dir = self.fnm_dir.as_ref()?;
+        let versions = fnm_dir.join("node-versions");
+        let aliases = fnm_dir.join("aliases");
+
+        let install = |path: &Path| {
+            let version = path.strip_prefix(&versions).ok()?.iter().next()?;
+            Some(NodeInstall::new(
+                versions.join(version).join("installation"),
+                &version.to_string_lossy(),
+            ))
+        };
+
+        ctx.command_files().find_map(|path| {
+            if let Some(found) = install(path) {
+                return Some((found, ctx.target_of(path)));
+            }
+
+            // fnm_multishells/<pid>_<timestamp> and aliases/<name> link to an installation
+            let link = path.ancestors().find(|dir| {
+                dir.parent().is_some_and(|parent| {
+                    parent.file_name().is_some_and(|n| n == "fnm_multishells") || parent == aliases
+                })
+            })?;
+            let real = link
+                .canonicalize()
+                .ok()?
+                .join(path.strip_prefix(link).ok()?);
+            install(&real).map(|found| (found, real))
+        })
+    }
+}
+
+impl PackageManagerDetector for FnmDetector {
+    fn id(&self) -> &'static str {
+        "fnm"
+    }
+
+    fn name(&self) -> &str {
+        "fnm"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true // fnm is cross-platform
+    }
+
+    fn priority(&self) -> i32 {
+        95 // Same as n, ahead of npm (90) for global packages inside the version
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let (install, resolved_path) = self.find_install(ctx)?;
+        Some(node_install_result(
+            ctx,
+            self.id(),
+            self.name(),
+            &install,
+            resolved_path,
+        ))
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        match self.find_install(ctx) {
+            Some((install, _)) => verify_node_install(ctx, result, &install),
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let (install, _) = self.find_install(ctx)?;
+        node_install_info(ctx, result, &install)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.to_path_buf(),
+            symlink_chain: vec![path.to_path_buf()],
+            resolved_path: path.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    /// An fnm dir with Node 18.19.0 installed.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir
+            .path()
+            .join("fnm/node-versions/v18.19.0/installation/bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("node"), "").unwrap();
+        dir
+    }
+
+    fn detector(dir: &Path) -> FnmDetector {
+        FnmDetector {
+            fnm_dir: Some(dir.canonicalize().unwrap().join("fnm")),
+        }
+    }
+
+    #[test]
+    fn test_detects_installation() {
+        let dir = fixture();
+        let node = dir
+            .path()
+            .join("fnm/node-versions/v18.19.0/installation/bin/node");
+        let detector = detector(dir.path());
+        let ctx = make_context("node", &node);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "fnm");
+        assert_eq!(result.package_name, Some("node".to_string()));
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolves_multishell_symlink() {
+        let dir = fixture();
+        let multishells = dir.path().join("state/fnm_multishells");
+        std::fs::create_dir_all(&multishells).unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("fnm/node-versions/v18.19.0/installation"),
+            multishells.join("4242_1700000000000"),
+        )
+        .unwrap();
+
+        let node = multishells.join("4242_1700000000000/bin/node");
+        let detector = detector(dir.path());
+        let ctx = make_context("node", &node);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+        assert_eq!(
+            result.resolved_path,
+            detector
+                .fnm_dir
+                .unwrap()
+                .join("node-versions/v18.19.0/installation/bin/node")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolves_default_alias() {
+        let dir = fixture();
+        std::fs::create_dir_all(dir.path().join("fnm/aliases")).unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("fnm/node-versions/v18.19.0/installation"),
+            dir.path().join("fnm/aliases/default"),
+        )
+        .unwrap();
+
+        let detector = detector(dir.path());
+        let node = detector
+            .fnm_dir
+            .as_ref()
+            .unwrap()
+            .join("aliases/default/bin/node");
+        let result = detector.detect(&make_context("node", &node)).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let dir = fixture();
+        assert!(detector(dir.path())
+            .detect(&make_context("node", Path::new("/usr/local/bin/node")))
+            .is_none());
+    }
+}
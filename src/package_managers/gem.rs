@@ -1,5 +1,28 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+// RubyGems binstubs load the executable through the owning gem:
+//   load Gem.activate_bin_path('rubocop', 'rubocop', version)
+//   load Gem.bin_path('rails', 'rails', version)
+static BINSTUB_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"Gem\.(?:activate_)?bin_path\(\s*['"]([^'"]+)['"]"#).unwrap());
+
+// Fields of an installed gemspec, e.g. `s.summary = "...".freeze`
+static GEMSPEC_STRING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*s\.(summary|homepage) = "([^"]*)""#).unwrap());
+static GEMSPEC_LIST_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*s\.(licenses|executables) = \[([^\]]*)\]"#).unwrap());
+static QUOTED_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([^"]*)""#).unwrap());
+
+/// Binstubs are tiny Ruby scripts; anything larger is not one.
+const MAX_BINSTUB_SIZE: u64 = 64 * 1024;
 
 /// Detector for RubyGems installed packages.
 pub struct GemDetector;
@@ -52,7 +75,10 @@ impl PackageManagerDetector for GemDetector {
                         manager_name: self.name().to_string(),
                         package_name: Some(ctx.command_name.clone()),
                         version: None,
+                        details: Default::default(),
                         confidence: Confidence::Medium,
+                        verification: Verification::Unverified,
+                        info: None,
                         command_path: ctx.command_path.clone(),
                         resolved_path: ctx.resolved_path.clone(),
                     });
@@ -62,6 +88,98 @@ impl PackageManagerDetector for GemDetector {
 
         None
     }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(gem) = read_binstub_gem(&ctx.resolved_path) else {
+            return Verification::Unverified;
+        };
+
+        // Binstubs live in {GEM_HOME}/bin, next to {GEM_HOME}/specifications;
+        // a binstub without an installed `<gem>-<version>.gemspec` is stale
+        let gem_home = ctx.resolved_path.parent().and_then(Path::parent);
+        let Some(version) = gem_home.and_then(|home| installed_gem_version(home, &gem)) else {
+            return Verification::Contradicted;
+        };
+        result.package_name = Some(gem);
+        result.version = Some(version);
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let gem = read_binstub_gem(&ctx.resolved_path).or_else(|| result.package_name.clone())?;
+        let gem_home = ctx.resolved_path.parent()?.parent()?;
+        let version = result
+            .version
+            .clone()
+            .or_else(|| installed_gem_version(gem_home, &gem))?;
+        let gemspec = gem_home
+            .join("specifications")
+            .join(format!("{gem}-{version}.gemspec"));
+        read_gemspec_info(&gemspec)
+    }
+}
+
+fn read_gemspec_info(gemspec: &Path) -> Option<PackageInfo> {
+    let content = std::fs::read_to_string(gemspec).ok()?;
+    let mut info = PackageInfo {
+        install_date: modified_time(gemspec),
+        ..Default::default()
+    };
+
+    for caps in GEMSPEC_STRING_REGEX.captures_iter(&content) {
+        let value = Some(caps[2].to_string());
+        match &caps[1] {
+            "summary" => info.description = value,
+            _ => info.homepage = value,
+        }
+    }
+    for caps in GEMSPEC_LIST_REGEX.captures_iter(&content) {
+        let values: Vec<String> = QUOTED_REGEX
+            .captures_iter(&caps[2])
+            .map(|c| c[1].to_string())
+            .collect();
+        match &caps[1] {
+            "licenses" => info.license = (!values.is_empty()).then(|| values.join(" OR ")),
+            _ => info.provides = values,
+        }
+    }
+    info.provides.sort();
+    Some(info)
+}
+
+/// Read the gem name from a RubyGems binstub.
+fn read_binstub_gem(path: &Path) -> Option<String> {
+    if std::fs::metadata(path).ok()?.len() > MAX_BINSTUB_SIZE {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    BINSTUB_REGEX
+        .captures(&content)
+        .map(|caps| caps[1].to_string())
+}
+
+/// Find the newest installed version of `gem` from `{GEM_HOME}/specifications`.
+fn installed_gem_version(gem_home: &Path, gem: &str) -> Option<String> {
+    let prefix = format!("{gem}-");
+    std::fs::read_dir(gem_home.join("specifications"))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let version = name.strip_prefix(&prefix)?.strip_suffix(".gemspec")?;
+            // Guard against gems sharing a prefix (e.g. "rails-html-sanitizer")
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| version.to_string())
+        })
+        .max_by_key(|v| version_key(v))
+}
+
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
 }
 
 #[cfg(test)]
@@ -78,6 +196,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -171,4 +290,99 @@ mod tests {
         assert!(detector.supports_platform(Platform::MacOS));
         assert!(detector.supports_platform(Platform::Linux));
     }
+
+    #[test]
+    fn test_verify_reads_binstub_and_specifications() {
+        let dir = tempfile::tempdir().unwrap();
+        let gem_home = dir.path().join(".gem/ruby/3.2.0");
+        std::fs::create_dir_all(gem_home.join("bin")).unwrap();
+        std::fs::create_dir_all(gem_home.join("specifications")).unwrap();
+        std::fs::write(
+            gem_home.join("bin/rubocop"),
+            "#!/usr/bin/env ruby\nversion = \">= 0.a\"\nload Gem.activate_bin_path('rubocop', 'rubocop', version)\n",
+        )
+        .unwrap();
+        for spec in ["rubocop-1.9.0", "rubocop-1.59.0", "rubocop-ast-1.30.0"] {
+            std::fs::write(gem_home.join(format!("specifications/{spec}.gemspec")), "").unwrap();
+        }
+        let bin = gem_home.join("bin/rubocop");
+
+        let detector = GemDetector::new();
+        let ctx = make_context("rubocop", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("rubocop".to_string()));
+        assert_eq!(result.version, Some("1.59.0".to_string()));
+    }
+
+    #[test]
+    fn test_verify_binstub_without_gemspec_is_contradicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let gem_home = dir.path().join(".gem/ruby/3.2.0");
+        std::fs::create_dir_all(gem_home.join("bin")).unwrap();
+        std::fs::create_dir_all(gem_home.join("specifications")).unwrap();
+        std::fs::write(
+            gem_home.join("bin/rubocop"),
+            "#!/usr/bin/env ruby
+load Gem.activate_bin_path('rubocop', 'rubocop', version)
+",
+        )
+        .unwrap();
+        // Only a gem sharing the name as a prefix is installed
+        std::fs::write(
+            gem_home.join("specifications/rubocop-ast-1.30.0.gemspec"),
+            "",
+        )
+        .unwrap();
+        let bin = gem_home.join("bin/rubocop");
+
+        let detector = GemDetector::new();
+        let ctx = make_context("rubocop", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(
+            detector.verify(&ctx, &mut result),
+            Verification::Contradicted
+        );
+    }
+
+    #[test]
+    fn test_read_gemspec_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let gemspec = dir.path().join("rubocop-1.59.0.gemspec");
+        std::fs::write(
+            &gemspec,
+            "Gem::Specification.new do |s|\n  s.name = \"rubocop\".freeze\n  \
+             s.executables = [\"rubocop\".freeze]\n  \
+             s.homepage = \"https://github.com/rubocop/rubocop\".freeze\n  \
+             s.licenses = [\"MIT\".freeze]\n  \
+             s.summary = \"Automatic Ruby code style checking tool.\".freeze\nend\n",
+        )
+        .unwrap();
+
+        let info = read_gemspec_info(&gemspec).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Automatic Ruby code style checking tool.")
+        );
+        assert_eq!(
+            info.homepage.as_deref(),
+            Some("https://github.com/rubocop/rubocop")
+        );
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(info.provides, vec!["rubocop"]);
+    }
+
+    #[test]
+    fn test_verify_non_binstub_is_unverified() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin_dir = dir.path().join(".gem/ruby/3.2.0/bin");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        std::fs::write(bin_dir.join("tool"), "#!/bin/sh\necho hi\n").unwrap();
+        let bin = bin_dir.join("tool");
+
+        let detector = GemDetector::new();
+        let ctx = make_context("tool", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
 }
@@ -1,5 +1,22 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::binary::{read_section, SectionName};
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use std::path::Path;
+
+/// Start of the build info blob the Go linker embeds in every binary.
+const BUILDINFO_MAGIC: &[u8] = b"\xff Go buildinf:";
+
+/// Where the linker puts the blob: its own section on ELF and Mach-O, the
+/// start of the data section on Windows.
+const BUILDINFO_SECTION: SectionName = SectionName {
+    elf: ".go.buildinfo",
+    macho: ("__DATA", "__go_buildinfo"),
+    pe: ".data",
+};
 
 /// Detector for Go installed packages (go install).
 pub struct GoDetector;
@@ -10,6 +27,17 @@ impl GoDetector {
     }
 }
 
+/// Check for Go bin paths:
+/// Unix: ~/go/bin/ or $GOPATH/bin/ or $GOBIN
+/// Windows: %USERPROFILE%\go\bin\
+fn is_go_bin_path(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    path_str.contains("/go/bin/")
+        || path_str.ends_with("/go/bin")
+        || path_str.contains(r"\go\bin\")
+        || path_str.ends_with(r"\go\bin")
+}
+
 impl PackageManagerDetector for GoDetector {
     fn id(&self) -> &'static str {
         "go"
@@ -28,38 +56,389 @@ impl PackageManagerDetector for GoDetector {
     }
 
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
-        for path in &ctx.symlink_chain {
-            let path_str = path.to_string_lossy();
-
-            // Check for Go bin paths:
-            // Unix: ~/go/bin/ or $GOPATH/bin/ or $GOBIN
-            // Windows: %USERPROFILE%\go\bin\
-            if path_str.contains("/go/bin/")
-                || path_str.ends_with("/go/bin")
-                || path_str.contains(r"\go\bin\")
-                || path_str.ends_with(r"\go\bin")
-            {
-                return Some(DetectionResult {
-                    manager_id: self.id().to_string(),
-                    manager_name: self.name().to_string(),
-                    package_name: Some(ctx.command_name.clone()),
-                    version: None,
-                    confidence: Confidence::Medium,
-                    command_path: ctx.command_path.clone(),
-                    resolved_path: ctx.resolved_path.clone(),
-                });
+        if !ctx.symlink_chain.iter().any(|p| is_go_bin_path(p)) {
+            return None;
+        }
+
+        // The module path comes from the build info during verification
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(ctx.command_name.clone()),
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(info) = read_build_info(&ctx.resolved_path) else {
+            return Verification::Unverified;
+        };
+        result.version = info.version();
+        result.package_name = Some(info.path);
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let build_info = read_build_info(&ctx.resolved_path)?;
+        let mut info = build_info.package_info(&ctx.resolved_path);
+        // `go install` is always explicit
+        info.installed_on_request = Some(true);
+        Some(info)
+    }
+}
+
+/// Detector for Go binaries outside a Go bin directory, identified only by
+/// their embedded build info. Something else may have put the binary there,
+/// so this is a weak signal checked after the real package managers.
+pub struct GoBinaryDetector;
+
+impl GoBinaryDetector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl PackageManagerDetector for GoBinaryDetector {
+    fn id(&self) -> &'static str {
+        "go_binary"
+    }
+
+    fn name(&self) -> &str {
+        "Go binary (build info)"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true
+    }
+
+    fn priority(&self) -> i32 {
+        15 // Just above the system fallback
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        // `GoDetector` reports binaries in Go bin directories
+        if ctx.symlink_chain.iter().any(|p| is_go_bin_path(p)) {
+            return None;
+        }
+        let info = read_build_info(&ctx.resolved_path)?;
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(info.path.clone()),
+            version: info.version(),
+            details: Default::default(),
+            confidence: Confidence::Low,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        Some(read_build_info(&ctx.resolved_path)?.package_info(&ctx.resolved_path))
+    }
+}
+
+/// A `dep` line, with the `=>` line that replaced it, if any.
+#[derive(Debug, PartialEq)]
+struct Dependency {
+    path: String,
+    version: String,
+    replacement: Option<(String, String)>,
+}
+
+/// Build information embedded by the Go linker (what `go version -m` prints).
+#[derive(Debug, Default, PartialEq)]
+struct BuildInfo {
+    /// Toolchain that built the binary (`go1.22.0`)
+    go_version: String,
+    /// Main package path (`github.com/x-motemen/ghq`, `golang.org/x/tools/cmd/goimports`)
+    path: String,
+    /// Main module path and version
+    module: Option<(String, String)>,
+    /// Dependency modules, in the order the linker recorded them
+    deps: Vec<Dependency>,
+    /// `build` settings (`vcs.revision`, `-ldflags`, ...)
+    settings: Vec<(String, String)>,
+}
+
+impl BuildInfo {
+    /// The main module version; binaries built from a checkout report `(devel)`.
+    fn version(&self) -> Option<String> {
+        self.module
+            .as_ref()
+            .map(|(_, version)| version.clone())
+            .filter(|version| version != "(devel)")
+    }
+
+    fn setting(&self, key: &str) -> Option<String> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    }
+
+    fn package_info(&self, binary: &Path) -> PackageInfo {
+        let mut info = PackageInfo {
+            install_date: modified_time(binary),
+            ..Default::default()
+        };
+        let mut extra = vec![("go_version", Some(self.go_version.clone()))];
+        if let Some((module, _)) = self.module.as_ref().filter(|(m, _)| *m != self.path) {
+            extra.push(("module", Some(module.clone())));
+        }
+        extra.extend([
+            ("revision", self.setting("vcs.revision")),
+            ("commit_time", self.setting("vcs.time")),
+            ("uncommitted_changes", self.setting("vcs.modified")),
+        ]);
+        if !self.deps.is_empty() {
+            extra.push(("dependencies", Some(format!("{} modules", self.deps.len()))));
+            extra.push(("dependency_list", Some(self.render_deps())));
+        }
+        for (key, value) in extra {
+            if let Some(value) = value {
+                info.extra.insert(key.to_string(), value);
+            }
+        }
+        info
+    }
+
+    /// One `path version` line per module, like `go version -m`, with
+    /// replacements on an indented `=>` line below.
+    fn render_deps(&self) -> String {
+        let mut lines = Vec::new();
+        for dep in &self.deps {
+            lines.push(format!("{} {}", dep.path, dep.version));
+            if let Some((path, version)) = &dep.replacement {
+                // Local directory replacements have no version
+                lines.push(format!("    => {path} {version}").trim_end().to_string());
             }
         }
+        lines.join("\n")
+    }
+}
+
+fn read_build_info(path: &Path) -> Option<BuildInfo> {
+    let data = read_section(path, &BUILDINFO_SECTION)?;
+    // The blob is 16-byte aligned, near the start of the section
+    let start = (0..data.len())
+        .step_by(16)
+        .find(|&i| data[i..].starts_with(BUILDINFO_MAGIC))?;
+    parse_build_info(&data[start..])
+}
 
-        None
+/// Parse the blob: a 32-byte header, then the Go version and module info as
+/// length-prefixed strings. Go before 1.18 stored pointers instead, which
+/// are not followed here.
+fn parse_build_info(blob: &[u8]) -> Option<BuildInfo> {
+    const FLAGS_VERSION_INLINE: u8 = 0x2;
+    let flags = *blob.get(15)?;
+    if flags & FLAGS_VERSION_INLINE == 0 {
+        return None;
     }
+
+    let mut rest = blob.get(32..)?;
+    let go_version = String::from_utf8_lossy(read_varint_bytes(&mut rest)?).to_string();
+    let mut modinfo = read_varint_bytes(&mut rest)?;
+    // Module info is wrapped in 16-byte sentinels
+    if modinfo.len() >= 33 && modinfo[modinfo.len() - 17] == b'\n' {
+        modinfo = &modinfo[16..modinfo.len() - 16];
+    }
+
+    let mut info = BuildInfo {
+        go_version,
+        ..Default::default()
+    };
+    for line in String::from_utf8_lossy(modinfo).lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let module = || Some((fields.get(1)?.to_string(), fields.get(2)?.to_string()));
+        match fields[0] {
+            "path" => info.path = fields.get(1).unwrap_or(&"").to_string(),
+            "mod" => info.module = module(),
+            "dep" => info.deps.extend(module().map(|(path, version)| Dependency {
+                path,
+                version,
+                replacement: None,
+            })),
+            // Replaces the module on the previous line
+            "=>" => {
+                if let Some(last) = info.deps.last_mut() {
+                    last.replacement = module();
+                }
+            }
+            "build" => {
+                if let Some((key, value)) = fields.get(1).and_then(|s| s.split_once('=')) {
+                    info.settings.push((key.to_string(), value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    (!info.path.is_empty()).then_some(info)
+}
+
+/// Read a uvarint length followed by that many bytes.
+fn read_varint_bytes<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let mut len = 0usize;
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        len |= ((byte & 0x7f) as usize).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    let bytes = data.get(..len)?;
+    *data = &data[len..];
+    Some(bytes)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::binary::tests::build_elf;
     use super::*;
     use std::path::PathBuf;
 
+    const GHQ_MODINFO: &str = "path\tgithub.com/x-motemen/ghq\n\
+        mod\tgithub.com/x-motemen/ghq\tv1.6.1\th1:abc=\n\
+        dep\tgithub.com/urfave/cli/v2\tv2.27.1\th1:def=\n\
+        dep\tgolang.org/x/sync\tv0.6.0\th1:ghi=\n\
+        =>\t../sync\t\t\n\
+        build\t-compiler=gc\n\
+        build\tvcs.revision=0a1b2c3d\n\
+        build\tvcs.modified=false\n";
+
+    /// A `.go.buildinfo` blob as written by Go 1.18+.
+    fn buildinfo_blob(go_version: &str, modinfo: &str) -> Vec<u8> {
+        let mut blob = BUILDINFO_MAGIC.to_vec();
+        blob.push(8); // pointer size
+        blob.push(0x2); // inline strings
+        blob.resize(32, 0);
+        blob.push(go_version.len() as u8);
+        blob.extend_from_slice(go_version.as_bytes());
+
+        let mut wrapped = vec![0x30u8; 16];
+        wrapped.extend_from_slice(modinfo.as_bytes());
+        wrapped.extend_from_slice(&[0xf9u8; 16]);
+        let mut len = wrapped.len();
+        while len >= 0x80 {
+            blob.push((len as u8) | 0x80);
+            len >>= 7;
+        }
+        blob.push(len as u8);
+        blob.extend_from_slice(&wrapped);
+        blob
+    }
+
+    fn write_go_binary(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut section = vec![0u8; 16]; // the blob is aligned, not at the start
+        section.extend(buildinfo_blob("go1.22.0", GHQ_MODINFO));
+        std::fs::write(path, build_elf(&[(".go.buildinfo", &section)], &[])).unwrap();
+    }
+
+    #[test]
+    fn test_parse_build_info() {
+        let info = parse_build_info(&buildinfo_blob("go1.22.0", GHQ_MODINFO)).unwrap();
+        assert_eq!(info.go_version, "go1.22.0");
+        assert_eq!(info.path, "github.com/x-motemen/ghq");
+        assert_eq!(info.version(), Some("v1.6.1".to_string()));
+        assert_eq!(
+            info.deps,
+            vec![
+                Dependency {
+                    path: "github.com/urfave/cli/v2".to_string(),
+                    version: "v2.27.1".to_string(),
+                    replacement: None,
+                },
+                Dependency {
+                    path: "golang.org/x/sync".to_string(),
+                    version: "v0.6.0".to_string(),
+                    replacement: Some(("../sync".to_string(), String::new())),
+                },
+            ]
+        );
+        assert_eq!(info.setting("vcs.revision"), Some("0a1b2c3d".to_string()));
+    }
+
+    #[test]
+    fn test_parse_devel_build() {
+        let info = parse_build_info(&buildinfo_blob(
+            "go1.21.5",
+            "path\texample.com/tool\nmod\texample.com/tool\t(devel)\t\n",
+        ))
+        .unwrap();
+        assert_eq!(info.version(), None);
+    }
+
+    #[test]
+    fn test_reads_build_info_from_go_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let ghq = dir.path().join("go/bin/ghq");
+        write_go_binary(&ghq);
+
+        let detector = GoDetector::new();
+        let ctx = make_context("ghq", vec![ghq.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("ghq".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(
+            result.package_name,
+            Some("github.com/x-motemen/ghq".to_string())
+        );
+        assert_eq!(result.version, Some("v1.6.1".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.extra["go_version"], "go1.22.0");
+        assert_eq!(info.extra["revision"], "0a1b2c3d");
+        assert_eq!(info.extra["dependencies"], "2 modules");
+        assert_eq!(
+            info.extra["dependency_list"],
+            "github.com/urfave/cli/v2 v2.27.1\n\
+             golang.org/x/sync v0.6.0\n    \
+             => ../sync"
+        );
+    }
+
+    #[test]
+    fn test_go_binary_outside_go_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let ghq = dir.path().join("opt/bin/ghq");
+        write_go_binary(&ghq);
+        let ctx = make_context("ghq", vec![ghq.to_str().unwrap()], Platform::Linux);
+
+        assert!(GoDetector::new().detect(&ctx).is_none());
+        let result = GoBinaryDetector::new().detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "go_binary");
+        assert_eq!(
+            result.package_name,
+            Some("github.com/x-motemen/ghq".to_string())
+        );
+        assert_eq!(result.confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn test_go_binary_ignores_other_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tool");
+        std::fs::write(&tool, build_elf(&[(".text", b"\x90")], &[])).unwrap();
+        let ctx = make_context("tool", vec![tool.to_str().unwrap()], Platform::Linux);
+        assert!(GoBinaryDetector::new().detect(&ctx).is_none());
+    }
+
     fn make_context(command: &str, paths: Vec<&str>, platform: Platform) -> DetectionContext {
         let command_path = PathBuf::from(paths.first().unwrap_or(&""));
         let resolved_path = PathBuf::from(paths.last().unwrap_or(&""));
@@ -69,6 +448,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -1,7 +1,12 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::info::{format_timestamp, list_commands};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::path::{Path, PathBuf};
 
 /// Detector for Homebrew package manager (macOS and Linux).
 pub struct HomebrewDetector;
@@ -21,6 +26,10 @@ static CELLAR_REGEX: Lazy<Regex> = Lazy::new(|| {
         .unwrap()
 });
 
+// Formula DSL fields in the copy of the formula kept at {keg}/.brew/{name}.rb
+static FORMULA_FIELD_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*(desc|homepage|license)\s+"([^"]*)""#).unwrap());
+
 impl PackageManagerDetector for HomebrewDetector {
     fn id(&self) -> &'static str {
         "homebrew"
@@ -39,8 +48,8 @@ impl PackageManagerDetector for HomebrewDetector {
     }
 
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
-        // Check all paths in the symlink chain for Cellar pattern
-        for path in &ctx.symlink_chain {
+        // Check the symlink chain and wrapper targets for the Cellar pattern
+        for path in ctx.command_files() {
             let path_str = path.to_string_lossy();
 
             if let Some(captures) = CELLAR_REGEX.captures(&path_str) {
@@ -52,7 +61,11 @@ impl PackageManagerDetector for HomebrewDetector {
                     manager_name: self.name().to_string(),
                     package_name,
                     version,
-                    confidence: Confidence::High,
+                    // Raised to High once the keg's INSTALL_RECEIPT.json is found
+                    details: Default::default(),
+                    confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
@@ -70,7 +83,10 @@ impl PackageManagerDetector for HomebrewDetector {
                 manager_name: self.name().to_string(),
                 package_name: None,
                 version: None,
-                confidence: Confidence::Medium,
+                details: Default::default(),
+                confidence: Confidence::Low,
+                verification: Verification::Unverified,
+                info: None,
                 command_path: ctx.command_path.clone(),
                 resolved_path: ctx.resolved_path.clone(),
             });
@@ -78,6 +94,66 @@ impl PackageManagerDetector for HomebrewDetector {
 
         None
     }
+
+    fn verify(&self, ctx: &DetectionContext, _result: &mut DetectionResult) -> Verification {
+        // Every poured or built keg carries an INSTALL_RECEIPT.json at its root
+        match find_keg(ctx) {
+            Some(keg) => verify_keg(&keg),
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let keg = find_keg(ctx)?;
+        Some(read_keg_info(&keg, result.package_name.as_deref()?))
+    }
+}
+
+/// Find the `Cellar/{package}/{version}` keg directory the command is in.
+fn find_keg(ctx: &DetectionContext) -> Option<PathBuf> {
+    ctx.command_files().find_map(|path| {
+        let path_str = path.to_string_lossy();
+        CELLAR_REGEX
+            .find(&path_str)
+            .map(|keg| PathBuf::from(keg.as_str()))
+    })
+}
+
+fn verify_keg(keg_dir: &Path) -> Verification {
+    if keg_dir.join("INSTALL_RECEIPT.json").is_file() {
+        Verification::Verified
+    } else {
+        Verification::Unverified
+    }
+}
+
+fn read_keg_info(keg_dir: &Path, package: &str) -> PackageInfo {
+    let mut info = PackageInfo::default();
+
+    if let Ok(formula) =
+        std::fs::read_to_string(keg_dir.join(".brew").join(format!("{package}.rb")))
+    {
+        for caps in FORMULA_FIELD_REGEX.captures_iter(&formula) {
+            let value = Some(caps[2].to_string());
+            match &caps[1] {
+                "desc" => info.description = info.description.or(value),
+                "homepage" => info.homepage = info.homepage.or(value),
+                _ => info.license = info.license.or(value),
+            }
+        }
+    }
+
+    if let Some(receipt) = std::fs::read_to_string(keg_dir.join("INSTALL_RECEIPT.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    {
+        info.installed_on_request = receipt["installed_on_request"].as_bool();
+        info.install_date = receipt["time"].as_u64().map(format_timestamp);
+    }
+
+    info.provides = list_commands(&keg_dir.join("bin"));
+    info.provides.extend(list_commands(&keg_dir.join("sbin")));
+    info
 }
 
 #[cfg(test)]
@@ -94,6 +170,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -146,7 +223,8 @@ mod tests {
         assert_eq!(result.manager_id, "homebrew");
         assert_eq!(result.package_name, Some("git".to_string()));
         assert_eq!(result.version, Some("2.51.2".to_string()));
-        assert_eq!(result.confidence, Confidence::High);
+        // Only the receipt check in `verify` makes it High
+        assert_eq!(result.confidence, Confidence::Medium);
     }
 
     #[test]
@@ -189,7 +267,7 @@ mod tests {
         let result = detector.detect(&ctx);
         assert!(result.is_some());
         let result = result.unwrap();
-        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(result.confidence, Confidence::Low);
         assert!(result.package_name.is_none()); // Can't extract from this path
     }
 
@@ -201,6 +279,52 @@ mod tests {
         assert!(detector.supports_platform(Platform::Linux));
     }
 
+    #[test]
+    fn test_verify_keg_with_receipt() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("INSTALL_RECEIPT.json"), "{}").unwrap();
+        assert_eq!(verify_keg(dir.path()), Verification::Verified);
+    }
+
+    #[test]
+    fn test_verify_keg_without_receipt() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(verify_keg(dir.path()), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_read_keg_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let keg = dir.path();
+        std::fs::create_dir_all(keg.join(".brew")).unwrap();
+        std::fs::create_dir_all(keg.join("bin")).unwrap();
+        std::fs::write(
+            keg.join(".brew/git.rb"),
+            "class Git < Formula\n  desc \"Distributed revision control system\"\n  \
+             homepage \"https://git-scm.com\"\n  license \"GPL-2.0-only\"\nend\n",
+        )
+        .unwrap();
+        std::fs::write(
+            keg.join("INSTALL_RECEIPT.json"),
+            r#"{"installed_on_request": false, "time": 1700000000}"#,
+        )
+        .unwrap();
+        for cmd in ["git", "git-shell", "scalar"] {
+            std::fs::write(keg.join("bin").join(cmd), "").unwrap();
+        }
+
+        let info = read_keg_info(keg, "git");
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Distributed revision control system")
+        );
+        assert_eq!(info.homepage.as_deref(), Some("https://git-scm.com"));
+        assert_eq!(info.license.as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(info.installed_on_request, Some(false));
+        assert_eq!(info.install_date.as_deref(), Some("2023-11-14T22:13:20Z"));
+        assert_eq!(info.provides, vec!["git", "git-shell", "scalar"]);
+    }
+
     #[test]
     fn test_non_homebrew_path() {
         let detector = HomebrewDetector::new();
@@ -0,0 +1,95 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Detailed package metadata, collected only when `--info` is requested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PackageInfo {
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    /// Install time as an RFC 3339 UTC timestamp
+    pub install_date: Option<String>,
+    /// `true` if explicitly requested, `false` if pulled in as a dependency
+    pub installed_on_request: Option<bool>,
+    /// Where the package came from (repository, channel, registry)
+    pub source: Option<String>,
+    /// Other commands provided by the same package
+    pub provides: Vec<String>,
+    /// Manager-specific details (e.g. a Flatpak runtime), keyed in snake_case
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+pub(super) fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// Last modification time of `path`, used as an install date when the
+/// package manager does not record one.
+pub(super) fn modified_time(path: &Path) -> Option<String> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(format_timestamp(secs))
+}
+
+/// Sorted names of the files in a `bin`-style directory.
+pub(super) fn list_commands(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut commands: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    commands.sort();
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_epoch() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn formats_leap_day() {
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn lists_commands_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["rg", "bat", ".hidden"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("subdir")).unwrap();
+        assert_eq!(list_commands(dir.path()), vec!["bat", "rg"]);
+    }
+}
@@ -1,16 +1,335 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::info::{list_commands, modified_time};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Per-directory config files, highest precedence first (`{env}` is `MISE_ENV`).
+const CONFIG_FILES: &[&str] = &[
+    "mise.local.toml",
+    ".mise.local.toml",
+    "mise.{env}.toml",
+    ".mise.{env}.toml",
+    "mise.toml",
+    ".mise.toml",
+    "mise/config.toml",
+    ".mise/config.toml",
+    ".config/mise.toml",
+    ".config/mise/config.toml",
+    ".tool-versions",
+];
 
 /// Detector for mise (formerly rtx) installed packages.
 /// mise is a polyglot runtime manager (like asdf).
-pub struct MiseDetector;
+pub struct MiseDetector {
+    /// `MISE_DATA_DIR`, or the platform default (`~/.local/share/mise`)
+    data_dir: Option<PathBuf>,
+    /// `MISE_CONFIG_DIR`, or `~/.config/mise`
+    config_dir: Option<PathBuf>,
+    home: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+    /// `MISE_*` environment variables
+    env: HashMap<String, String>,
+}
 
 impl MiseDetector {
     pub fn new() -> Self {
-        Self
+        let env: HashMap<String, String> = std::env::vars()
+            .filter(|(key, _)| key.starts_with("MISE_"))
+            .collect();
+        let home = dirs::home_dir();
+
+        let data_dir = env
+            .get("MISE_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| default_data_dir(home.as_deref()));
+        let config_dir = env.get("MISE_CONFIG_DIR").map(PathBuf::from).or_else(|| {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| h.join(".config")))
+                .map(|d| d.join("mise"))
+        });
+
+        Self {
+            data_dir,
+            config_dir,
+            home,
+            cwd: std::env::current_dir().ok(),
+            env,
+        }
+    }
+
+    /// Tool versions requested by the effective configuration, most specific first.
+    fn requested_tools(&self) -> Vec<ToolRequest> {
+        let mut requests = Vec::new();
+
+        // MISE_<TOOL>_VERSION overrides every config file
+        for (key, value) in &self.env {
+            if let Some(tool) = key
+                .strip_prefix("MISE_")
+                .and_then(|k| k.strip_suffix("_VERSION"))
+            {
+                requests.push(ToolRequest {
+                    tool: tool.to_lowercase().replace('_', "-"),
+                    versions: vec![value.clone()],
+                    origin: key.clone(),
+                });
+            }
+        }
+
+        let mise_env = self.env.get("MISE_ENV");
+        let file_names: Vec<String> = CONFIG_FILES
+            .iter()
+            .filter_map(|name| match (name.contains("{env}"), mise_env) {
+                (true, Some(env)) => Some(name.replace("{env}", env)),
+                (true, None) => None,
+                (false, _) => Some(name.to_string()),
+            })
+            .collect();
+
+        if let Some(ref cwd) = self.cwd {
+            for dir in cwd.ancestors() {
+                for name in &file_names {
+                    requests.extend(read_config(&dir.join(name)));
+                }
+            }
+        }
+
+        if let Some(file) = self.env.get("MISE_GLOBAL_CONFIG_FILE") {
+            requests.extend(read_config(Path::new(file)));
+        }
+        if let Some(ref config_dir) = self.config_dir {
+            requests.extend(read_config(&config_dir.join("config.toml")));
+        }
+        if let Some(ref home) = self.home {
+            requests.extend(read_config(&home.join(".tool-versions")));
+        }
+
+        // Keep the most specific request for each tool
+        let mut seen = std::collections::HashSet::new();
+        requests.retain(|r| seen.insert(r.tool.clone()));
+        requests
+    }
+
+    /// Resolve a shimmed command to the tool and installed version providing it.
+    fn resolve_shim(&self, installs: &Path, command: &str) -> Option<ShimTarget> {
+        for request in self.requested_tools() {
+            let tool_dir = installs.join(install_dir_name(&request.tool));
+            for version in &request.versions {
+                let Some(installed) = resolve_version(&tool_dir, version) else {
+                    continue;
+                };
+                if let Some(binary) = find_binary(&tool_dir.join(&installed), command) {
+                    return Some(ShimTarget {
+                        tool: request.tool,
+                        version: Some(installed),
+                        binary: Some(binary),
+                        origin: Some(request.origin),
+                    });
+                }
+            }
+        }
+
+        // Not selected by any config: name the installed tool that provides it
+        let entries = std::fs::read_dir(installs).ok()?;
+        let mut tools: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        tools.sort();
+        tools.into_iter().find_map(|tool_dir| {
+            let provides = std::fs::read_dir(&tool_dir)
+                .ok()?
+                .filter_map(|e| e.ok())
+                .any(|v| find_binary(&v.path(), command).is_some());
+            provides.then(|| ShimTarget {
+                tool: tool_dir
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                version: None,
+                binary: None,
+                origin: None,
+            })
+        })
+    }
+
+    fn result(
+        &self,
+        ctx: &DetectionContext,
+        package_name: Option<String>,
+        version: Option<String>,
+        resolved_path: PathBuf,
+        confidence: Confidence,
+    ) -> DetectionResult {
+        DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name,
+            version,
+            details: Default::default(),
+            confidence,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path,
+        }
+    }
+}
+
+/// `%LOCALAPPDATA%\mise` on Windows; `$XDG_DATA_HOME/mise` or `~/.local/share/mise` elsewhere.
+fn default_data_dir(home: Option<&Path>) -> Option<PathBuf> {
+    if cfg!(windows) {
+        return dirs::data_local_dir().map(|d| d.join("mise"));
+    }
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".local/share")))
+        .map(|d| d.join("mise"))
+}
+
+/// A `tool = version` entry from one config source.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ToolRequest {
+    pub(super) tool: String,
+    /// Requested versions in preference order (a prefix, `latest`, or exact)
+    pub(super) versions: Vec<String>,
+    /// Config file or environment variable the request came from
+    pub(super) origin: String,
+}
+
+/// What a shim dispatches to.
+#[derive(Debug, PartialEq)]
+struct ShimTarget {
+    tool: String,
+    version: Option<String>,
+    binary: Option<PathBuf>,
+    /// Config file or environment variable that selected the version
+    origin: Option<String>,
+}
+
+/// Read the tool requests from a `mise.toml`-style file or a `.tool-versions` file.
+fn read_config(path: &Path) -> Vec<ToolRequest> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let origin = path.display().to_string();
+
+    if path.file_name().is_some_and(|n| n == ".tool-versions") {
+        return parse_tool_versions(&content, &origin);
+    }
+
+    let Ok(config) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(tools) = config.get("tools").and_then(|t| t.as_table()) else {
+        return Vec::new();
+    };
+
+    tools
+        .iter()
+        .filter_map(|(tool, value)| {
+            let versions: Vec<String> = match value {
+                toml::Value::String(v) => vec![v.clone()],
+                toml::Value::Array(values) => values
+                    .iter()
+                    .filter_map(|v| match v {
+                        toml::Value::String(s) => Some(s.clone()),
+                        toml::Value::Table(t) => t.get("version")?.as_str().map(str::to_string),
+                        _ => None,
+                    })
+                    .collect(),
+                toml::Value::Table(t) => vec![t.get("version")?.as_str()?.to_string()],
+                _ => return None,
+            };
+            Some(ToolRequest {
+                tool: tool.clone(),
+                versions,
+                origin: origin.clone(),
+            })
+        })
+        .collect()
+}
+
+/// `.tool-versions` lines: `<tool> <version> [<fallback version>...]`
+pub(super) fn parse_tool_versions(content: &str, origin: &str) -> Vec<ToolRequest> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let tool = parts.next()?;
+            let versions: Vec<String> = parts.map(str::to_string).collect();
+            (!versions.is_empty()).then(|| ToolRequest {
+                tool: tool.to_string(),
+                versions,
+                origin: origin.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Backend-qualified tools are installed under a flattened name
+/// (`npm:prettier` -> `npm-prettier`, `aqua:cli/cli` -> `aqua-cli-cli`).
+fn install_dir_name(tool: &str) -> String {
+    tool.replace([':', '/'], "-")
+}
+
+/// Map a requested version to an installed version directory. mise accepts
+/// prefixes (`20` -> `20.10.0`) and `latest`.
+fn resolve_version(tool_dir: &Path, requested: &str) -> Option<String> {
+    let requested = requested.strip_prefix("prefix:").unwrap_or(requested);
+
+    // Exact matches, including mise's own alias symlinks (`20 -> 20.10.0`)
+    let exact = tool_dir.join(requested);
+    if exact.is_dir() {
+        let target = exact.canonicalize().unwrap_or(exact);
+        return target.file_name().map(|n| n.to_string_lossy().to_string());
+    }
+
+    let entries = std::fs::read_dir(tool_dir).ok()?;
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|v| requested == "latest" || v.starts_with(&format!("{requested}.")))
+        .max_by(|a, b| compare_versions(a, b))
+}
+
+/// Compare dotted versions numerically where possible (`3.10.0` > `3.9.1`).
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    _ => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
     }
 }
 
+/// Look for `command` in an install directory (`bin/` or the top level).
+fn find_binary(install_dir: &Path, command: &str) -> Option<PathBuf> {
+    [
+        install_dir.join("bin").join(command),
+        install_dir.join(command),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
 impl PackageManagerDetector for MiseDetector {
     fn id(&self) -> &'static str {
         "mise"
@@ -29,7 +348,55 @@ impl PackageManagerDetector for MiseDetector {
     }
 
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
-        for path in &ctx.symlink_chain {
+        if let Some(ref data_dir) = self.data_dir {
+            let shims = data_dir.join("shims");
+            let installs = data_dir.join("installs");
+
+            // Medium until verification finds the binary in the install directory
+            for path in ctx.command_files() {
+                if path.parent() == Some(shims.as_path()) {
+                    let command = path.file_name()?.to_string_lossy().to_string();
+                    return Some(match self.resolve_shim(&installs, &command) {
+                        Some(ShimTarget {
+                            tool,
+                            version,
+                            binary: Some(binary),
+                            ..
+                        }) => self.result(ctx, Some(tool), version, binary, Confidence::Medium),
+                        Some(target) => self.result(
+                            ctx,
+                            Some(target.tool),
+                            None,
+                            ctx.resolved_path.clone(),
+                            Confidence::Medium,
+                        ),
+                        None => self.result(
+                            ctx,
+                            None,
+                            None,
+                            ctx.resolved_path.clone(),
+                            Confidence::Medium,
+                        ),
+                    });
+                }
+
+                // installs/<tool>/<version>/...
+                if let Ok(relative) = path.strip_prefix(&installs) {
+                    let mut parts = relative.iter().map(|p| p.to_string_lossy().to_string());
+                    let tool = parts.next();
+                    let version = parts.next();
+                    return Some(self.result(
+                        ctx,
+                        tool,
+                        version,
+                        ctx.resolved_path.clone(),
+                        Confidence::Medium,
+                    ));
+                }
+            }
+        }
+
+        for path in ctx.command_files() {
             let path_str = path.to_string_lossy();
 
             // Check for mise paths:
@@ -44,20 +411,67 @@ impl PackageManagerDetector for MiseDetector {
                 || path_str.ends_with(r"\mise\shims")
             {
                 let tool_name = extract_mise_tool_name(&path_str);
-                return Some(DetectionResult {
-                    manager_id: self.id().to_string(),
-                    manager_name: self.name().to_string(),
-                    package_name: tool_name,
-                    version: None,
-                    confidence: Confidence::Medium,
-                    command_path: ctx.command_path.clone(),
-                    resolved_path: ctx.resolved_path.clone(),
-                });
+                return Some(self.result(
+                    ctx,
+                    tool_name,
+                    None,
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ));
             }
         }
 
         None
     }
+
+    fn verify(&self, _ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let (Some(data_dir), Some(tool), Some(version)) = (
+            self.data_dir.as_ref(),
+            result.package_name.as_deref(),
+            result.version.as_deref(),
+        ) else {
+            return Verification::Unverified;
+        };
+        let install_dir = data_dir
+            .join("installs")
+            .join(install_dir_name(tool))
+            .join(version);
+        if result.resolved_path.starts_with(&install_dir) && result.resolved_path.is_file() {
+            Verification::Verified
+        } else {
+            Verification::Unverified
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let data_dir = self.data_dir.as_ref()?;
+        let install_dir = data_dir
+            .join("installs")
+            .join(install_dir_name(result.package_name.as_deref()?))
+            .join(result.version.as_deref()?);
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&install_dir),
+            installed_on_request: Some(true),
+            provides: list_commands(&install_dir.join("bin")),
+            ..Default::default()
+        };
+
+        let shims = data_dir.join("shims");
+        if let Some(shim) = ctx
+            .command_files()
+            .find(|p| p.parent() == Some(shims.as_path()))
+        {
+            let command = shim.file_name()?.to_string_lossy().to_string();
+            if let Some(origin) = self
+                .resolve_shim(&data_dir.join("installs"), &command)
+                .and_then(|target| target.origin)
+            {
+                info.extra.insert("selected_by".to_string(), origin);
+            }
+        }
+        Some(info)
+    }
 }
 
 fn extract_mise_tool_name(path: &str) -> Option<String> {
@@ -99,6 +513,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -193,4 +608,147 @@ mod tests {
         assert!(detector.supports_platform(Platform::MacOS));
         assert!(detector.supports_platform(Platform::Linux));
     }
+
+    /// A mise data dir with node 20.10.0 / 18.19.0 and npm:prettier installed.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("data");
+        std::fs::create_dir_all(data.join("shims")).unwrap();
+        for (tool, version, bin) in [
+            ("node", "20.10.0", "node"),
+            ("node", "18.19.0", "node"),
+            ("npm-prettier", "3.1.1", "prettier"),
+        ] {
+            let bin_dir = data.join("installs").join(tool).join(version).join("bin");
+            std::fs::create_dir_all(&bin_dir).unwrap();
+            std::fs::write(bin_dir.join(bin), "").unwrap();
+        }
+        std::fs::create_dir_all(dir.path().join("config/mise")).unwrap();
+        std::fs::create_dir_all(dir.path().join("project/sub")).unwrap();
+        dir
+    }
+
+    fn fixture_detector(root: &Path, cwd: &Path, env: &[(&str, &str)]) -> MiseDetector {
+        MiseDetector {
+            data_dir: Some(root.join("data")),
+            config_dir: Some(root.join("config/mise")),
+            home: Some(root.to_path_buf()),
+            cwd: Some(cwd.to_path_buf()),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn shim_context(root: &Path, command: &str) -> DetectionContext {
+        let shim = root.join("data/shims").join(command);
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: shim.clone(),
+            symlink_chain: vec![shim.clone()],
+            resolved_path: shim,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolves_shim_from_project_config() {
+        let root = fixture();
+        std::fs::write(
+            root.path().join("project/mise.toml"),
+            "[tools]\nnode = \"18\"\n",
+        )
+        .unwrap();
+        let detector = fixture_detector(root.path(), &root.path().join("project/sub"), &[]);
+        let ctx = shim_context(root.path(), "node");
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("node".to_string()));
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+        assert_eq!(
+            result.resolved_path,
+            root.path().join("data/installs/node/18.19.0/bin/node")
+        );
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector
+            .info(&shim_context(root.path(), "node"), &result)
+            .unwrap();
+        assert!(info.extra["selected_by"].ends_with("mise.toml"));
+        assert_eq!(info.provides, vec!["node"]);
+    }
+
+    #[test]
+    fn test_resolves_shim_from_global_config() {
+        let root = fixture();
+        std::fs::write(
+            root.path().join("config/mise/config.toml"),
+            "[tools]\nnode = \"latest\"\n\"npm:prettier\" = { version = \"3\" }\n",
+        )
+        .unwrap();
+        let detector = fixture_detector(root.path(), &root.path().join("project"), &[]);
+
+        let node = detector.detect(&shim_context(root.path(), "node")).unwrap();
+        assert_eq!(node.version, Some("20.10.0".to_string()));
+
+        let prettier = detector
+            .detect(&shim_context(root.path(), "prettier"))
+            .unwrap();
+        assert_eq!(prettier.package_name, Some("npm:prettier".to_string()));
+        assert_eq!(prettier.version, Some("3.1.1".to_string()));
+    }
+
+    #[test]
+    fn test_tool_versions_and_env_override() {
+        let root = fixture();
+        std::fs::write(root.path().join("project/.tool-versions"), "node 20.10.0\n").unwrap();
+        let cwd = root.path().join("project");
+
+        let detector = fixture_detector(root.path(), &cwd, &[]);
+        let result = detector.detect(&shim_context(root.path(), "node")).unwrap();
+        assert_eq!(result.version, Some("20.10.0".to_string()));
+
+        let detector = fixture_detector(root.path(), &cwd, &[("MISE_NODE_VERSION", "18.19.0")]);
+        let result = detector.detect(&shim_context(root.path(), "node")).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+    }
+
+    #[test]
+    fn test_unconfigured_shim_names_installed_tool() {
+        let root = fixture();
+        let detector = fixture_detector(root.path(), &root.path().join("project"), &[]);
+        let ctx = shim_context(root.path(), "prettier");
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("npm-prettier".to_string()));
+        assert_eq!(result.version, None);
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_custom_data_dir_installs() {
+        let root = fixture();
+        let detector = fixture_detector(root.path(), root.path(), &[]);
+        let node = root.path().join("data/installs/node/20.10.0/bin/node");
+        let ctx = DetectionContext {
+            command_name: "node".to_string(),
+            command_path: node.clone(),
+            symlink_chain: vec![node.clone()],
+            resolved_path: node,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        };
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("node".to_string()));
+        assert_eq!(result.version, Some("20.10.0".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("3.10.0", "3.9.1"), Ordering::Greater);
+        assert_eq!(compare_versions("20.1", "20.1.0"), Ordering::Less);
+    }
 }
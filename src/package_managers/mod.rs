@@ -1,22 +1,50 @@
+mod anyenv;
+mod asdf;
+mod auditable;
+mod binary;
 mod bun;
 mod cargo;
+mod conda;
+mod custom;
+mod fnm;
 mod gem;
 mod go;
 mod homebrew;
+mod info;
 mod mise;
 mod n;
 mod nix;
 mod node_global;
 mod npm;
+mod nvm;
+mod pip;
 mod pipx;
 mod pnpm;
+mod python;
+mod rustup;
 mod system;
+mod uv;
+mod volta;
 mod yarn;
 
+#[cfg(target_os = "linux")]
+mod apk;
+#[cfg(target_os = "linux")]
+mod appimage;
 #[cfg(target_os = "linux")]
 mod apt;
 #[cfg(target_os = "linux")]
+mod dpkg;
+#[cfg(target_os = "linux")]
+mod flatpak;
+#[cfg(target_os = "linux")]
+mod pacman;
+#[cfg(target_os = "linux")]
+mod rpm;
+#[cfg(target_os = "linux")]
 mod snap;
+#[cfg(target_os = "linux")]
+mod squashfs;
 
 #[cfg(target_os = "windows")]
 mod chocolatey;
@@ -25,9 +53,12 @@ mod scoop;
 #[cfg(target_os = "windows")]
 mod winget;
 
+pub use info::PackageInfo;
+
 use crate::platform::Platform;
 use serde::Serialize;
 use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Detection context containing all information about the command being analyzed.
@@ -38,6 +69,70 @@ pub struct DetectionContext {
     pub symlink_chain: Vec<PathBuf>,
     pub resolved_path: PathBuf,
     pub platform: Platform,
+    /// Programs the resolved file hands off to when it is a script, in order
+    pub script_links: Vec<ScriptLink>,
+}
+
+impl DetectionContext {
+    /// The interpreter the command finally runs under, if it is a script.
+    pub fn interpreter(&self) -> Option<&Path> {
+        self.script_links
+            .iter()
+            .rev()
+            .find(|link| link.kind == LinkKind::InterpretedBy)
+            .map(|link| link.path.as_path())
+    }
+
+    /// The files that are the command: the symlink chain, then the programs
+    /// wrapper scripts `exec` into.
+    pub fn command_files(&self) -> impl DoubleEndedIterator<Item = &PathBuf> {
+        self.symlink_chain.iter().chain(
+            self.script_links
+                .iter()
+                .filter(|link| link.kind == LinkKind::Execs)
+                .map(|link| &link.path),
+        )
+    }
+
+    /// Where running `path` (one of `command_files`) ends up: the end of the
+    /// symlink chain, or the wrapper target itself.
+    pub fn target_of(&self, path: &Path) -> PathBuf {
+        if self.symlink_chain.iter().any(|p| p == path) {
+            self.resolved_path.clone()
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Paths that can place the command in a managed location: its files,
+    /// then its interpreter (a script run by a pipx venv's Python belongs to
+    /// that venv). Detectors that report the runtime itself (nvm's `node`)
+    /// use `command_files`, or every script run by it would be claimed.
+    pub fn candidate_paths(&self) -> impl DoubleEndedIterator<Item = &PathBuf> {
+        let interpreter = self
+            .script_links
+            .iter()
+            .rev()
+            .find(|link| link.kind == LinkKind::InterpretedBy)
+            .map(|link| &link.path);
+        self.command_files().chain(interpreter)
+    }
+}
+
+/// How a script hands off to the next program in its chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Named in the shebang (or pip's `exec` trick) and given the script to run
+    InterpretedBy,
+    /// Replaced the wrapper script through `exec /path/to/real "$@"`
+    Execs,
+}
+
+/// A step after the symlink chain, found by reading a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLink {
+    pub kind: LinkKind,
+    pub path: PathBuf,
 }
 
 /// Result of a successful detection.
@@ -47,22 +142,40 @@ pub struct DetectionResult {
     pub manager_name: String,
     pub package_name: Option<String>,
     pub version: Option<String>,
+    /// Manager-specific facts known at detection time (e.g. `architecture`),
+    /// keyed in snake_case
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
     pub confidence: Confidence,
+    pub verification: Verification,
+    /// Detailed package metadata (only populated with `--info`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<PackageInfo>,
     #[serde(serialize_with = "serialize_path")]
     pub command_path: PathBuf,
     #[serde(serialize_with = "serialize_path")]
     pub resolved_path: PathBuf,
 }
 
-fn serialize_path<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_path<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
     serializer.serialize_str(&path.display().to_string())
 }
 
-/// Confidence level of the detection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+fn serialize_optional_path<S>(path: &Option<PathBuf>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match path {
+        Some(path) => serialize_path(path, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Confidence level of the detection (ordered from most to least confident).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Confidence {
     /// Verified with package manager query
@@ -75,10 +188,35 @@ pub enum Confidence {
     Uncertain,
 }
 
+/// One of several detectors that matched a command (see `--all-matches`).
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectionCandidate {
+    #[serde(flatten)]
+    pub result: DetectionResult,
+    /// Priority of the detector that produced this match
+    pub priority: i32,
+    /// Path the detector matched (a chain element, an exec target or the
+    /// interpreter), if it can be pinned down
+    #[serde(serialize_with = "serialize_optional_path")]
+    pub matched_path: Option<PathBuf>,
+}
+
+/// Outcome of the verification phase that runs after a path match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verification {
+    /// Package manager metadata confirms it owns the file
+    Verified,
+    /// Package manager metadata disagrees with the path match
+    Contradicted,
+    /// Not verified (skipped, or no metadata to check against)
+    Unverified,
+}
+
 /// Trait for package manager detectors.
 pub trait PackageManagerDetector: Send + Sync {
     /// Unique identifier for this package manager.
-    fn id(&self) -> &'static str;
+    fn id(&self) -> &str;
 
     /// Human-readable name.
     fn name(&self) -> &str;
@@ -91,6 +229,19 @@ pub trait PackageManagerDetector: Send + Sync {
 
     /// Attempt to detect if command was installed by this package manager.
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult>;
+
+    /// Confirm a result returned by `detect` by consulting the package manager's
+    /// own metadata. Implementations may fill in details (e.g. version) that the
+    /// path alone could not provide.
+    fn verify(&self, _ctx: &DetectionContext, _result: &mut DetectionResult) -> Verification {
+        Verification::Unverified
+    }
+
+    /// Collect detailed package metadata (description, homepage, other
+    /// commands in the package, ...) for a detected result.
+    fn info(&self, _ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        None
+    }
 }
 
 /// Registry of all package manager detectors.
@@ -103,23 +254,43 @@ impl PackageManagerRegistry {
         let mut detectors: Vec<Box<dyn PackageManagerDetector>> = vec![
             Box::new(homebrew::HomebrewDetector::new()),
             Box::new(n::NDetector::new()),
+            Box::new(nvm::NvmDetector::new()),
+            Box::new(fnm::FnmDetector::new()),
+            Box::new(volta::VoltaDetector::new()),
             Box::new(npm::NpmGlobalDetector::new()),
             Box::new(bun::BunGlobalDetector::new()),
+            Box::new(rustup::RustupDetector::new()),
             Box::new(cargo::CargoDetector::new()),
             Box::new(pipx::PipxDetector::new()),
+            Box::new(uv::UvDetector::new()),
+            Box::new(conda::CondaDetector::new()),
+            Box::new(pip::PipDetector::new()),
             Box::new(go::GoDetector::new()),
             Box::new(yarn::YarnGlobalDetector::new()),
             Box::new(pnpm::PnpmGlobalDetector::new()),
             Box::new(gem::GemDetector::new()),
             Box::new(mise::MiseDetector::new()),
+            Box::new(asdf::AsdfDetector::new()),
+            Box::new(anyenv::AnyenvDetector::new(&anyenv::PYENV)),
+            Box::new(anyenv::AnyenvDetector::new(&anyenv::RBENV)),
+            Box::new(anyenv::AnyenvDetector::new(&anyenv::NODENV)),
+            Box::new(anyenv::AnyenvDetector::new(&anyenv::GOENV)),
+            Box::new(anyenv::AnyenvDetector::new(&anyenv::JENV)),
             Box::new(nix::NixDetector::new()),
+            Box::new(go::GoBinaryDetector::new()),
+            Box::new(auditable::AuditableDetector::new()),
             Box::new(system::SystemDetector::new()),
         ];
 
         #[cfg(target_os = "linux")]
         {
             detectors.push(Box::new(apt::AptDetector::new()));
+            detectors.push(Box::new(rpm::RpmDetector::new()));
+            detectors.push(Box::new(pacman::PacmanDetector::new()));
+            detectors.push(Box::new(apk::ApkDetector::new()));
             detectors.push(Box::new(snap::SnapDetector::new()));
+            detectors.push(Box::new(flatpak::FlatpakDetector::new()));
+            detectors.push(Box::new(appimage::AppImageDetector::new()));
         }
 
         #[cfg(target_os = "windows")]
@@ -135,6 +306,24 @@ impl PackageManagerRegistry {
         Self { detectors }
     }
 
+    /// Create a registry with the built-in detectors plus those defined in a
+    /// custom rules file (see `--database`).
+    pub fn with_database(path: &Path) -> crate::error::Result<Self> {
+        let mut registry = Self::new();
+        // "unknown" is the result when nothing matches
+        let reserved: Vec<&str> = registry
+            .detectors
+            .iter()
+            .map(|d| d.id())
+            .chain(["unknown"])
+            .collect();
+        for detector in custom::load_rules(path, &reserved)? {
+            registry.detectors.push(Box::new(detector));
+        }
+        registry.detectors.sort_by_key(|d| Reverse(d.priority()));
+        Ok(registry)
+    }
+
     /// Try to detect the package manager for the given context.
     pub fn detect(&self, ctx: &DetectionContext, verbose: bool) -> Option<DetectionResult> {
         for detector in &self.detectors {
@@ -155,6 +344,112 @@ impl PackageManagerRegistry {
         }
         None
     }
+
+    /// Run every supported detector and collect all matches, in priority order.
+    /// Unlike `detect`, this does not stop at the first match, so layered
+    /// installs (e.g. an npm global package inside a mise-managed Node) show
+    /// up as several candidates.
+    pub fn detect_all(&self, ctx: &DetectionContext, verbose: bool) -> Vec<DetectionCandidate> {
+        let mut candidates = Vec::new();
+
+        for detector in &self.detectors {
+            if !detector.supports_platform(ctx.platform) {
+                continue;
+            }
+
+            if verbose {
+                eprintln!("Trying {}...", detector.name());
+            }
+
+            if let Some(result) = detector.detect(ctx) {
+                if verbose {
+                    eprintln!("✓ Matched: {}", detector.name());
+                }
+                candidates.push(DetectionCandidate {
+                    result,
+                    priority: detector.priority(),
+                    matched_path: matched_candidate_path(detector.as_ref(), ctx),
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Run the verification phase for a result produced by `detect`,
+    /// upgrading or downgrading its confidence accordingly.
+    pub fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult, verbose: bool) {
+        let Some(detector) = self.detectors.iter().find(|d| d.id() == result.manager_id) else {
+            return;
+        };
+
+        if verbose {
+            eprintln!("Verifying with {}...", detector.name());
+        }
+
+        match detector.verify(ctx, result) {
+            Verification::Verified => {
+                if verbose {
+                    eprintln!("✓ Verified: {}", detector.name());
+                }
+                result.confidence = Confidence::High;
+                result.verification = Verification::Verified;
+            }
+            Verification::Contradicted => {
+                if verbose {
+                    eprintln!("✗ Contradicted: {}", detector.name());
+                }
+                result.confidence = Confidence::Low;
+                result.verification = Verification::Contradicted;
+            }
+            Verification::Unverified => {
+                if verbose {
+                    eprintln!("- Could not verify with {}", detector.name());
+                }
+            }
+        }
+    }
+
+    /// Attach detailed package metadata from the detector that produced `result`.
+    pub fn info(&self, ctx: &DetectionContext, result: &mut DetectionResult, verbose: bool) {
+        let Some(detector) = self.detectors.iter().find(|d| d.id() == result.manager_id) else {
+            return;
+        };
+
+        if verbose {
+            eprintln!("Collecting package information from {}...", detector.name());
+        }
+
+        result.info = detector.info(ctx, result);
+    }
+}
+
+/// Find which of the command's paths a detector keys on, by probing each of
+/// `candidate_paths` on its own (first link to the final target, then the
+/// scripts it hands off to and its interpreter).
+fn matched_candidate_path(
+    detector: &dyn PackageManagerDetector,
+    ctx: &DetectionContext,
+) -> Option<PathBuf> {
+    ctx.candidate_paths()
+        .find(|path| {
+            let probe = DetectionContext {
+                command_name: ctx.command_name.clone(),
+                command_path: (*path).clone(),
+                symlink_chain: vec![(*path).clone()],
+                resolved_path: (*path).clone(),
+                platform: ctx.platform,
+                script_links: Vec::new(),
+            };
+            detector.detect(&probe).is_some()
+        })
+        .cloned()
+}
+
+/// Sort candidates from most to least likely: by confidence, then by
+/// detector priority. Ties keep their registry order.
+pub fn rank_candidates(candidates: &mut [DetectionCandidate]) {
+    candidates.sort_by_key(|c| (c.result.confidence, Reverse(c.priority)));
 }
 
 impl Default for PackageManagerRegistry {
@@ -162,3 +457,113 @@ impl Default for PackageManagerRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&str>, platform: Platform) -> DetectionContext {
+        let command_path = PathBuf::from(paths.first().unwrap_or(&""));
+        let resolved_path = PathBuf::from(paths.last().unwrap_or(&""));
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: command_path.clone(),
+            symlink_chain: paths.iter().map(PathBuf::from).collect(),
+            resolved_path,
+            platform,
+            script_links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_detect_all_reports_layered_install() {
+        let registry = PackageManagerRegistry::new();
+        let ctx = make_context(
+            "tsc",
+            vec![
+                "/home/u/.local/share/mise/installs/node/20/bin/tsc",
+                "/home/u/.local/share/mise/installs/node/20/lib/node_modules/typescript/bin/tsc",
+            ],
+            Platform::MacOS,
+        );
+
+        let candidates = registry.detect_all(&ctx, false);
+        let ids: Vec<&str> = candidates
+            .iter()
+            .map(|c| c.result.manager_id.as_str())
+            .collect();
+        assert!(ids.contains(&"npm_global"));
+        assert!(ids.contains(&"mise"));
+
+        let npm = candidates
+            .iter()
+            .find(|c| c.result.manager_id == "npm_global")
+            .unwrap();
+        assert_eq!(npm.result.package_name, Some("typescript".to_string()));
+        assert_eq!(npm.matched_path, Some(ctx.symlink_chain[1].clone()));
+
+        let mise = candidates
+            .iter()
+            .find(|c| c.result.manager_id == "mise")
+            .unwrap();
+        assert_eq!(mise.matched_path, Some(ctx.symlink_chain[0].clone()));
+    }
+
+    #[test]
+    fn test_detect_all_matches_exec_target() {
+        let registry = PackageManagerRegistry::new();
+        let target = PathBuf::from("/home/u/.local/share/mise/installs/node/20/bin/node");
+        let mut ctx = make_context("node", vec!["/opt/wrappers/node"], Platform::Linux);
+        ctx.script_links.push(ScriptLink {
+            kind: LinkKind::Execs,
+            path: target.clone(),
+        });
+
+        let candidates = registry.detect_all(&ctx, false);
+        let mise = candidates
+            .iter()
+            .find(|c| c.result.manager_id == "mise")
+            .unwrap();
+        assert_eq!(mise.matched_path, Some(target));
+    }
+
+    #[test]
+    fn test_detect_all_empty_when_nothing_matches() {
+        let registry = PackageManagerRegistry::new();
+        let ctx = make_context("tool", vec!["/opt/unknown/tool"], Platform::MacOS);
+        assert!(registry.detect_all(&ctx, false).is_empty());
+    }
+
+    #[test]
+    fn test_rank_candidates_by_confidence_then_priority() {
+        let candidate = |id: &str, confidence, priority| DetectionCandidate {
+            result: DetectionResult {
+                manager_id: id.to_string(),
+                manager_name: id.to_string(),
+                package_name: None,
+                version: None,
+                details: Default::default(),
+                confidence,
+                verification: Verification::Unverified,
+                info: None,
+                command_path: PathBuf::new(),
+                resolved_path: PathBuf::new(),
+            },
+            priority,
+            matched_path: None,
+        };
+        let mut candidates = vec![
+            candidate("low", Confidence::Low, 100),
+            candidate("medium", Confidence::Medium, 50),
+            candidate("high", Confidence::High, 10),
+            candidate("medium-first", Confidence::Medium, 90),
+        ];
+
+        rank_candidates(&mut candidates);
+        let ids: Vec<&str> = candidates
+            .iter()
+            .map(|c| c.result.manager_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["high", "medium-first", "medium", "low"]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 use std::path::Path;
 
@@ -63,7 +63,10 @@ impl PackageManagerDetector for NDetector {
             manager_name: self.name().to_string(),
             package_name: Some(command_base.to_string()),
             version,
+            details: Default::default(),
             confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
             command_path: ctx.command_path.clone(),
             resolved_path: ctx.resolved_path.clone(),
         })
@@ -103,6 +106,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
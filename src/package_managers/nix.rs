@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for Nix packages.
@@ -71,7 +71,10 @@ impl PackageManagerDetector for NixDetector {
                 manager_name: self.name().to_string(),
                 package_name,
                 version,
+                details: Default::default(),
                 confidence: Confidence::Medium,
+                verification: Verification::Unverified,
+                info: None,
                 command_path: ctx.command_path.clone(),
                 resolved_path: ctx.resolved_path.clone(),
             });
@@ -149,6 +152,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -1,4 +1,7 @@
-use super::{Confidence, DetectionContext, DetectionResult};
+use super::info::{list_commands, modified_time};
+use super::{Confidence, DetectionContext, DetectionResult, PackageInfo, Verification};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Skip names that are never actual package names — `node_modules/.bin/` is
 /// the common bin symlink directory shared by every Node package manager.
@@ -72,12 +75,229 @@ where
         manager_name: manager_name.to_string(),
         package_name,
         version: None,
+        details: Default::default(),
         confidence: Confidence::Medium,
+        verification: Verification::Unverified,
+        info: None,
         command_path: ctx.command_path.clone(),
         resolved_path: ctx.resolved_path.clone(),
     })
 }
 
+/// Shared `verify()` body for Node-ecosystem global package managers: find the
+/// installed package directory in the chain and check its `package.json`.
+/// A matching `name` confirms the detection and supplies the version.
+pub(super) fn verify_node_global(
+    ctx: &DetectionContext,
+    result: &mut DetectionResult,
+) -> Verification {
+    let Some(package) = result.package_name.clone() else {
+        return Verification::Unverified;
+    };
+
+    let Some(package_dir) = ctx
+        .symlink_chain
+        .iter()
+        .find_map(|p| find_package_dir(p, &package))
+    else {
+        return Verification::Unverified;
+    };
+    verify_package_dir(&package_dir, &package, result)
+}
+
+/// Check that `<package_dir>/package.json` names `package`, taking its version.
+fn verify_package_dir(
+    package_dir: &Path,
+    package: &str,
+    result: &mut DetectionResult,
+) -> Verification {
+    let Some(manifest) = std::fs::read_to_string(package_dir.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else {
+        return Verification::Unverified;
+    };
+
+    if manifest["name"].as_str() != Some(package) {
+        return Verification::Contradicted;
+    }
+    if let Some(version) = manifest["version"].as_str() {
+        result.version = Some(version.to_string());
+    }
+    Verification::Verified
+}
+
+/// Shared `info()` body for Node-ecosystem global package managers, read
+/// from the installed package's `package.json`.
+pub(super) fn node_global_info(
+    ctx: &DetectionContext,
+    result: &DetectionResult,
+) -> Option<PackageInfo> {
+    let package = result.package_name.as_deref()?;
+    let package_dir = ctx
+        .symlink_chain
+        .iter()
+        .find_map(|p| find_package_dir(p, package))?;
+    package_json_info(package, &package_dir)
+}
+
+/// Package metadata from `<package_dir>/package.json`.
+fn package_json_info(package: &str, package_dir: &Path) -> Option<PackageInfo> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(package_dir.join("package.json")).ok()?)
+            .ok()?;
+
+    let string = |key: &str| manifest[key].as_str().map(str::to_string);
+    let mut provides: Vec<String> = match &manifest["bin"] {
+        // "bin": "./cli.js" installs a single command named after the package
+        serde_json::Value::String(_) => package
+            .rsplit('/')
+            .next()
+            .map(str::to_string)
+            .into_iter()
+            .collect(),
+        serde_json::Value::Object(bins) => bins.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    provides.sort();
+
+    Some(PackageInfo {
+        description: string("description"),
+        homepage: string("homepage"),
+        license: string("license"),
+        install_date: modified_time(package_dir),
+        // Global packages are only ever installed explicitly
+        installed_on_request: Some(true),
+        provides,
+        ..Default::default()
+    })
+}
+
+/// A Node.js installation owned by a version manager (nvm, fnm, volta).
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct NodeInstall {
+    /// Installation prefix containing `bin/` and `lib/node_modules/`
+    pub(super) prefix: PathBuf,
+    /// Node version, without the `v` prefix some managers use for directory names
+    pub(super) version: String,
+}
+
+impl NodeInstall {
+    pub(super) fn new(prefix: PathBuf, version: &str) -> Self {
+        Self {
+            prefix,
+            version: version.strip_prefix('v').unwrap_or(version).to_string(),
+        }
+    }
+}
+
+/// Shared `detect()` result for Node version managers. Commands from global
+/// npm packages report the package (its version is filled in by
+/// `verify_node_global`) and the Node version as the `node_version` detail;
+/// everything else reports Node itself. Confidence stays Medium until
+/// `verify_node_install` confirms the installation.
+pub(super) fn node_install_result(
+    ctx: &DetectionContext,
+    manager_id: &str,
+    manager: &str,
+    install: &NodeInstall,
+    resolved_path: PathBuf,
+) -> DetectionResult {
+    let package = ctx
+        .command_files()
+        .chain(std::iter::once(&resolved_path))
+        .find_map(|p| extract_node_modules_package_name(&p.to_string_lossy(), SKIP_BIN));
+    let (version, details) = match package {
+        Some(_) => (None, node_version_detail(install)),
+        None => (Some(install.version.clone()), BTreeMap::new()),
+    };
+
+    DetectionResult {
+        manager_id: manager_id.to_string(),
+        manager_name: manager.to_string(),
+        package_name: package.or_else(|| Some("node".to_string())),
+        version,
+        details,
+        confidence: Confidence::Medium,
+        verification: Verification::Unverified,
+        info: None,
+        command_path: ctx.command_path.clone(),
+        resolved_path,
+    }
+}
+
+/// The Node version a global package runs under, as a result detail.
+pub(super) fn node_version_detail(install: &NodeInstall) -> BTreeMap<String, String> {
+    [("node_version".to_string(), install.version.clone())].into()
+}
+
+/// Shared `verify()` body for Node version managers. Node itself is verified
+/// when the installation prefix exists and the resolved binary is a file;
+/// global packages are verified like `verify_node_global`, also looking at the
+/// result's resolved path (volta resolves shims itself).
+pub(super) fn verify_node_install(
+    ctx: &DetectionContext,
+    result: &mut DetectionResult,
+    install: &NodeInstall,
+) -> Verification {
+    let Some(package) = result.package_name.clone().filter(|p| p != "node") else {
+        return if install.prefix.is_dir() && result.resolved_path.is_file() {
+            Verification::Verified
+        } else {
+            Verification::Unverified
+        };
+    };
+    let Some(package_dir) = install_paths(ctx, result).find_map(|p| find_package_dir(p, &package))
+    else {
+        return Verification::Unverified;
+    };
+    verify_package_dir(&package_dir, &package, result)
+}
+
+/// Shared `info()` body for Node version managers: global packages are read
+/// from their `package.json`, Node itself from the installation prefix.
+pub(super) fn node_install_info(
+    ctx: &DetectionContext,
+    result: &DetectionResult,
+    install: &NodeInstall,
+) -> Option<PackageInfo> {
+    if result.package_name.as_deref() == Some("node") {
+        Some(PackageInfo {
+            install_date: modified_time(&install.prefix),
+            installed_on_request: Some(true),
+            provides: list_commands(&install.prefix.join("bin")),
+            ..Default::default()
+        })
+    } else {
+        let package = result.package_name.as_deref()?;
+        let package_dir = install_paths(ctx, result).find_map(|p| find_package_dir(p, package))?;
+        package_json_info(package, &package_dir)
+    }
+}
+
+/// The command's files followed by the detector's own resolution of them.
+fn install_paths<'a>(
+    ctx: &'a DetectionContext,
+    result: &'a DetectionResult,
+) -> impl Iterator<Item = &'a PathBuf> {
+    ctx.command_files()
+        .chain(std::iter::once(&result.resolved_path))
+}
+
+/// Find the `node_modules/<package>` directory that contains `path`.
+fn find_package_dir(path: &Path, package: &str) -> Option<PathBuf> {
+    let depth = Path::new(package).components().count();
+    path.ancestors()
+        .filter(|dir| dir.ends_with(package))
+        .find(|dir| {
+            dir.ancestors()
+                .nth(depth)
+                .and_then(|p| p.file_name())
+                .is_some_and(|name| name == "node_modules")
+        })
+        .map(Path::to_path_buf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +406,7 @@ mod tests {
             symlink_chain: vec![PathBuf::from("/Users/u/.bun/bin/vite")],
             resolved_path: PathBuf::from("/Users/u/.bun/bin/vite"),
             platform: Platform::MacOS,
+            script_links: Vec::new(),
         };
         let result = detect_node_global(
             &ctx,
@@ -221,4 +442,101 @@ mod tests {
             None
         );
     }
+
+    fn result_for(package: &str) -> DetectionResult {
+        DetectionResult {
+            manager_id: "npm_global".to_string(),
+            manager_name: "npm (global)".to_string(),
+            package_name: Some(package.to_string()),
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: PathBuf::new(),
+            resolved_path: PathBuf::new(),
+        }
+    }
+
+    fn context_for(path: PathBuf) -> DetectionContext {
+        DetectionContext {
+            command_name: "cmd".to_string(),
+            command_path: path.clone(),
+            symlink_chain: vec![path.clone()],
+            resolved_path: path,
+            platform: crate::platform::Platform::current(),
+            script_links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn verify_reads_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("lib/node_modules/@antfu/ni");
+        std::fs::create_dir_all(pkg.join("bin")).unwrap();
+        std::fs::write(
+            pkg.join("package.json"),
+            r#"{"name": "@antfu/ni", "version": "0.21.12"}"#,
+        )
+        .unwrap();
+
+        let ctx = context_for(pkg.join("bin/ni.mjs"));
+        let mut result = result_for("@antfu/ni");
+        assert_eq!(
+            verify_node_global(&ctx, &mut result),
+            Verification::Verified
+        );
+        assert_eq!(result.version, Some("0.21.12".to_string()));
+    }
+
+    #[test]
+    fn verify_detects_name_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("node_modules/typescript");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("package.json"), r#"{"name": "other"}"#).unwrap();
+
+        let ctx = context_for(pkg.join("bin/tsc"));
+        let mut result = result_for("typescript");
+        assert_eq!(
+            verify_node_global(&ctx, &mut result),
+            Verification::Contradicted
+        );
+    }
+
+    #[test]
+    fn info_reads_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let pkg = dir.path().join("node_modules/typescript");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(
+            pkg.join("package.json"),
+            r#"{"name": "typescript", "description": "TypeScript is a language",
+                "homepage": "https://www.typescriptlang.org/", "license": "Apache-2.0",
+                "bin": {"tsserver": "./bin/tsserver", "tsc": "./bin/tsc"}}"#,
+        )
+        .unwrap();
+
+        let ctx = context_for(pkg.join("bin/tsc"));
+        let info = node_global_info(&ctx, &result_for("typescript")).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("TypeScript is a language")
+        );
+        assert_eq!(info.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(info.installed_on_request, Some(true));
+        assert_eq!(info.provides, vec!["tsc", "tsserver"]);
+    }
+
+    #[test]
+    fn verify_without_package_json_is_unverified() {
+        let ctx = context_for(PathBuf::from(
+            "/nonexistent/node_modules/typescript/bin/tsc",
+        ));
+        let mut result = result_for("typescript");
+        assert_eq!(
+            verify_node_global(&ctx, &mut result),
+            Verification::Unverified
+        );
+    }
 }
@@ -0,0 +1,232 @@
+use super::node_global::{
+    node_install_info, node_install_result, verify_node_install, NodeInstall,
+};
+use super::{DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector, Verification};
+use crate::platform::Platform;
+use std::path::{Path, PathBuf};
+
+/// Detector for nvm (Node Version Manager) installed Node.js.
+/// nvm installs each version to `$NVM_DIR/versions/node/v<version>`.
+pub struct NvmDetector {
+    /// `NVM_DIR`, or `~/.nvm`
+    nvm_dir: Option<PathBuf>,
+}
+
+impl NvmDetector {
+    pub fn new() -> Self {
+        Self {
+            nvm_dir: std::env::var_os("NVM_DIR")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| dirs::home_dir().map(|home| home.join(".nvm"))),
+        }
+    }
+
+    /// The installation a path belongs to. `$NVM_DIR/current` (with
+    /// `NVM_SYMLINK_CURRENT`) is a directory symlink, so paths are also
+    /// checked after canonicalizing.
+    fn find_install(&self, ctx: &DetectionContext) -> Option<(NodeInstall, PathBuf)> {
+        let versions = self.nvm_dir.as_ref()?.join("versions/node");
+        ctx.command_files().find_map(|path| {
+            let install = |path: &Path| {
+                let version = path.strip_prefix(&versions).ok()?.iter().next()?;
+                Some(NodeInstall::new(
+                    versions.join(version),
+                    &version.to_string_lossy(),
+                ))
+            };
+            install(path).map(|i| (i, ctx.target_of(path))).or_else(|| {
+                let canonical = path.canonicalize().ok()?;
+                install(&canonical).map(|i| (i, canonical))
+            })
+        })
+    }
+}
+
+impl PackageManagerDetector for NvmDetector {
+    fn id(&self) -> &'static str {
+        "nvm"
+    }
+
+    fn name(&self) -> &str {
+        "nvm"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        // nvm is a POSIX shell script; nvm-windows is a separate project
+        matches!(platform, Platform::MacOS | Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        95 // Same as n, ahead of npm (90) for global packages inside the version
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let (install, resolved_path) = self.find_install(ctx)?;
+        Some(node_install_result(
+            ctx,
+            self.id(),
+            self.name(),
+            &install,
+            resolved_path,
+        ))
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        match self.find_install(ctx) {
+            Some((install, _)) => verify_node_install(ctx, result, &install),
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let (install, _) = self.find_install(ctx)?;
+        node_install_info(ctx, result, &install)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Confidence, LinkKind, ScriptLink};
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&Path>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    /// `$NVM_DIR` with Node 20.11.0 and a global typescript package.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("versions/node/v20.11.0");
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        std::fs::write(prefix.join("bin/node"), "").unwrap();
+        let package = prefix.join("lib/node_modules/typescript");
+        std::fs::create_dir_all(package.join("bin")).unwrap();
+        std::fs::write(
+            package.join("package.json"),
+            r#"{"name": "typescript", "version": "5.3.3", "bin": {"tsc": "./bin/tsc"}}"#,
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_detects_node() {
+        let dir = fixture();
+        let node = dir.path().join("versions/node/v20.11.0/bin/node");
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().to_path_buf()),
+        };
+        let ctx = make_context("node", vec![&node]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "nvm");
+        assert_eq!(result.manager_name, "nvm");
+        assert_eq!(result.package_name, Some("node".to_string()));
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert!(result.details.is_empty());
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.provides, vec!["node"]);
+    }
+
+    #[test]
+    fn test_detects_global_package() {
+        let dir = fixture();
+        let prefix = dir.path().join("versions/node/v20.11.0");
+        let bin = prefix.join("bin/tsc");
+        let target = prefix.join("lib/node_modules/typescript/bin/tsc");
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().to_path_buf()),
+        };
+        let ctx = make_context("tsc", vec![&bin, &target]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "nvm");
+        assert_eq!(result.package_name, Some("typescript".to_string()));
+        assert_eq!(result.version, None);
+        assert_eq!(result.details["node_version"], "20.11.0");
+
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.version, Some("5.3.3".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_current_symlink() {
+        let dir = fixture();
+        std::os::unix::fs::symlink(
+            dir.path().join("versions/node/v20.11.0"),
+            dir.path().join("current"),
+        )
+        .unwrap();
+        let node = dir.path().join("current/bin/node");
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().canonicalize().unwrap()),
+        };
+        let result = detector.detect(&make_context("node", vec![&node])).unwrap();
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+    }
+
+    #[test]
+    fn test_detects_wrapper_exec() {
+        let dir = fixture();
+        let prefix = dir.path().join("versions/node/v20.11.0");
+        let target = prefix.join("lib/node_modules/typescript/bin/tsc");
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().to_path_buf()),
+        };
+        // /usr/local/bin/tsc is a script running `exec <nvm>/.../tsc "$@"`
+        let mut ctx = make_context("tsc", vec![Path::new("/usr/local/bin/tsc")]);
+        ctx.script_links = vec![
+            ScriptLink {
+                kind: LinkKind::Execs,
+                path: target.clone(),
+            },
+            ScriptLink {
+                kind: LinkKind::InterpretedBy,
+                path: prefix.join("bin/node"),
+            },
+        ];
+
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "nvm");
+        assert_eq!(result.package_name, Some("typescript".to_string()));
+        assert_eq!(result.details["node_version"], "20.11.0");
+        assert_eq!(result.resolved_path, target);
+    }
+
+    #[test]
+    fn test_ignores_scripts_run_by_nvm_node() {
+        let dir = fixture();
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().to_path_buf()),
+        };
+        // `#!/usr/bin/env node` found nvm's node on PATH
+        let mut ctx = make_context("tool", vec![Path::new("/home/user/bin/tool")]);
+        ctx.script_links = vec![ScriptLink {
+            kind: LinkKind::InterpretedBy,
+            path: dir.path().join("versions/node/v20.11.0/bin/node"),
+        }];
+        assert!(detector.detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let dir = fixture();
+        let detector = NvmDetector {
+            nvm_dir: Some(dir.path().to_path_buf()),
+        };
+        assert!(detector
+            .detect(&make_context("node", vec![Path::new("/usr/bin/node")]))
+            .is_none());
+    }
+}
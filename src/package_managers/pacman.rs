@@ -0,0 +1,355 @@
+use super::info::format_timestamp;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Detector for pacman packages (Arch Linux and derivatives).
+/// Reads the local database (`/var/lib/pacman/local/<pkg>-<ver>/{desc,files}`).
+pub struct PacmanDetector {
+    local_dir: PathBuf,
+    /// File path -> package entry directory name
+    files: OnceCell<HashMap<PathBuf, String>>,
+}
+
+impl PacmanDetector {
+    pub fn new() -> Self {
+        Self::with_root(Path::new("/"))
+    }
+
+    /// Use the pacman database below `root` instead of the running system's.
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            local_dir: root.join("var/lib/pacman/local"),
+            files: OnceCell::new(),
+        }
+    }
+
+    /// Find the package entry that owns `path`. Arch links `/bin`, `/sbin`
+    /// and `/usr/sbin` to `/usr/bin`, so those paths are looked up there too.
+    fn find_owner(&self, path: &Path) -> Option<PacmanPackage> {
+        let files = self.files.get_or_init(|| load_file_index(&self.local_dir));
+
+        let path_str = path.to_string_lossy();
+        let merged = ["/bin/", "/sbin/", "/usr/sbin/"]
+            .iter()
+            .find_map(|prefix| path_str.strip_prefix(prefix))
+            .map(|rest| PathBuf::from("/usr/bin").join(rest));
+
+        let entry = files
+            .get(path)
+            .or_else(|| merged.and_then(|p| files.get(&p)))?;
+        self.read_package(entry)
+    }
+
+    /// Commands the package installs into `/usr/bin`.
+    fn package_commands(&self, package: &PacmanPackage) -> Vec<String> {
+        let entry = format!("{}-{}", package.name, package.version);
+        let Some(files) = self.files.get() else {
+            return Vec::new();
+        };
+        let mut commands: Vec<String> = files
+            .iter()
+            .filter(|(path, owner)| {
+                **owner == entry && path.parent() == Some(Path::new("/usr/bin"))
+            })
+            .filter_map(|(path, _)| path.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect();
+        commands.sort();
+        commands
+    }
+
+    fn read_package(&self, entry: &str) -> Option<PacmanPackage> {
+        let content = std::fs::read_to_string(self.local_dir.join(entry).join("desc")).ok()?;
+        PacmanPackage::from_desc(&content)
+    }
+}
+
+/// Metadata from a package's `desc` file.
+#[derive(Debug, PartialEq)]
+struct PacmanPackage {
+    name: String,
+    version: String,
+    description: Option<String>,
+    url: Option<String>,
+    licenses: Vec<String>,
+    install_date: Option<u64>,
+    /// `%REASON%` is `1` for packages pulled in as dependencies
+    explicit: bool,
+    /// Not installed from a signed repository package (AUR helper or `makepkg -i`)
+    foreign: bool,
+}
+
+impl PacmanPackage {
+    fn from_desc(content: &str) -> Option<Self> {
+        let fields = parse_desc(content);
+        let first = |key: &str| fields.get(key).and_then(|v| v.first()).cloned();
+
+        // Repository packages are signature-checked on install; locally built
+        // packages record `%VALIDATION% none` and makepkg's default packager.
+        let foreign = first("VALIDATION").as_deref() == Some("none")
+            || first("PACKAGER").as_deref() == Some("Unknown Packager");
+
+        Some(Self {
+            name: first("NAME")?,
+            version: first("VERSION")?,
+            description: first("DESC"),
+            url: first("URL"),
+            licenses: fields.get("LICENSE").cloned().unwrap_or_default(),
+            install_date: first("INSTALLDATE").and_then(|d| d.parse().ok()),
+            explicit: first("REASON").as_deref() != Some("1"),
+            foreign,
+        })
+    }
+}
+
+impl PackageManagerDetector for PacmanDetector {
+    fn id(&self) -> &'static str {
+        "pacman"
+    }
+
+    fn name(&self) -> &str {
+        "pacman"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        matches!(platform, Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        50 // Same level as apt
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        if !self.local_dir.is_dir() {
+            return None;
+        }
+
+        let package = self.find_owner(&ctx.resolved_path)?;
+        let manager_name = if package.foreign {
+            "pacman (foreign/AUR)".to_string()
+        } else {
+            self.name().to_string()
+        };
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name,
+            package_name: Some(package.name),
+            version: Some(package.version),
+            details: Default::default(),
+            confidence: Confidence::High,
+            // The local database itself records ownership, so no separate verification is needed
+            verification: Verification::Verified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let package = self.find_owner(&ctx.resolved_path)?;
+        let provides = self.package_commands(&package);
+        Some(PackageInfo {
+            description: package.description,
+            homepage: package.url,
+            license: (!package.licenses.is_empty()).then(|| package.licenses.join(", ")),
+            install_date: package.install_date.map(format_timestamp),
+            installed_on_request: Some(package.explicit),
+            source: Some(if package.foreign {
+                "foreign (AUR or local build)".to_string()
+            } else {
+                "repository".to_string()
+            }),
+            provides,
+            ..Default::default()
+        })
+    }
+}
+
+/// Parse the `%KEY%` sections of a `desc` or `files` file.
+fn parse_desc(content: &str) -> HashMap<String, Vec<String>> {
+    let mut fields: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if let Some(key) = line.strip_prefix('%').and_then(|l| l.strip_suffix('%')) {
+            current = Some(key.to_string());
+            fields.entry(key.to_string()).or_default();
+        } else if line.is_empty() {
+            current = None;
+        } else if let Some(ref key) = current {
+            fields
+                .entry(key.clone())
+                .or_default()
+                .push(line.to_string());
+        }
+    }
+    fields
+}
+
+/// Build the file -> package index from every `local/*/files` file.
+fn load_file_index(local_dir: &Path) -> HashMap<PathBuf, String> {
+    let mut index = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(local_dir) else {
+        return index;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(content) = std::fs::read_to_string(entry.path().join("files")) else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        let files = parse_desc(&content).remove("FILES").unwrap_or_default();
+        // Paths are relative to the root; directories end with '/'
+        for file in files.iter().filter(|f| !f.ends_with('/')) {
+            index.insert(Path::new("/").join(file), name.clone());
+        }
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &str) -> DetectionContext {
+        let path = PathBuf::from(path);
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.clone(),
+            symlink_chain: vec![path.clone()],
+            resolved_path: path,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn write_package(root: &Path, entry: &str, desc: &str, files: &[&str]) {
+        let dir = root.join("var/lib/pacman/local").join(entry);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("desc"), desc).unwrap();
+        std::fs::write(
+            dir.join("files"),
+            format!("%FILES%\n{}\n\n%BACKUP%\n", files.join("\n")),
+        )
+        .unwrap();
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write_package(
+            dir.path(),
+            "git-2.43.0-1",
+            "%NAME%\ngit\n\n%VERSION%\n2.43.0-1\n\n%DESC%\nthe fast distributed version control system\n\n\
+             %URL%\nhttps://git-scm.com/\n\n%INSTALLDATE%\n1700000000\n\n\
+             %PACKAGER%\nChristian Hesse <eworm@archlinux.org>\n\n%LICENSE%\nGPL2\n\n%VALIDATION%\npgp\n",
+            &["usr/", "usr/bin/", "usr/bin/git", "usr/bin/git-shell", "usr/lib/git-core/git"],
+        );
+        write_package(
+            dir.path(),
+            "zlib-1:1.3-1",
+            "%NAME%\nzlib\n\n%VERSION%\n1:1.3-1\n\n%REASON%\n1\n\n%VALIDATION%\npgp\n",
+            &["usr/lib/libz.so.1"],
+        );
+        write_package(
+            dir.path(),
+            "yay-12.1.0-1",
+            "%NAME%\nyay\n\n%VERSION%\n12.1.0-1\n\n%PACKAGER%\nUnknown Packager\n\n%VALIDATION%\nnone\n",
+            &["usr/bin/yay"],
+        );
+        dir
+    }
+
+    #[test]
+    fn test_parse_desc() {
+        let fields = parse_desc("%NAME%\ngit\n\n%LICENSE%\nGPL2\nLGPL2.1\n\n%EMPTY%\n\n");
+        assert_eq!(fields["NAME"], vec!["git"]);
+        assert_eq!(fields["LICENSE"], vec!["GPL2", "LGPL2.1"]);
+        assert!(fields["EMPTY"].is_empty());
+    }
+
+    #[test]
+    fn test_detects_repository_package() {
+        let root = fixture();
+        let detector = PacmanDetector::with_root(root.path());
+        let result = detector
+            .detect(&make_context("git", "/usr/bin/git"))
+            .unwrap();
+        assert_eq!(result.manager_id, "pacman");
+        assert_eq!(result.manager_name, "pacman");
+        assert_eq!(result.package_name, Some("git".to_string()));
+        assert_eq!(result.version, Some("2.43.0-1".to_string()));
+        assert_eq!(result.confidence, Confidence::High);
+    }
+
+    #[test]
+    fn test_detects_foreign_package() {
+        let root = fixture();
+        let detector = PacmanDetector::with_root(root.path());
+        let ctx = make_context("yay", "/usr/bin/yay");
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "pacman (foreign/AUR)");
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.source.as_deref(), Some("foreign (AUR or local build)"));
+    }
+
+    #[test]
+    fn test_usrmerge_paths() {
+        let root = fixture();
+        let detector = PacmanDetector::with_root(root.path());
+        let result = detector.detect(&make_context("git", "/bin/git")).unwrap();
+        assert_eq!(result.package_name, Some("git".to_string()));
+    }
+
+    #[test]
+    fn test_unowned_path() {
+        let root = fixture();
+        let detector = PacmanDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("rg", "/home/user/.cargo/bin/rg"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_missing_database() {
+        let root = tempfile::tempdir().unwrap();
+        let detector = PacmanDetector::with_root(root.path());
+        assert!(detector
+            .detect(&make_context("git", "/usr/bin/git"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_info() {
+        let root = fixture();
+        let detector = PacmanDetector::with_root(root.path());
+        let ctx = make_context("git", "/usr/bin/git");
+        let result = detector.detect(&ctx).unwrap();
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("the fast distributed version control system")
+        );
+        assert_eq!(info.license.as_deref(), Some("GPL2"));
+        assert_eq!(info.install_date.as_deref(), Some("2023-11-14T22:13:20Z"));
+        assert_eq!(info.installed_on_request, Some(true));
+        assert_eq!(info.source.as_deref(), Some("repository"));
+        assert_eq!(info.provides, vec!["git", "git-shell"]);
+    }
+
+    #[test]
+    fn test_dependency_reason() {
+        let package = PacmanPackage::from_desc(
+            "%NAME%\nzlib\n\n%VERSION%\n1:1.3-1\n\n%REASON%\n1\n\n%VALIDATION%\npgp\n",
+        )
+        .unwrap();
+        assert!(!package.explicit);
+        assert!(!package.foreign);
+    }
+}
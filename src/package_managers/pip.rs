@@ -0,0 +1,385 @@
+use super::info::modified_time;
+use super::python::{
+    dist_info_dirs, entry_point_scripts, parse_dist_info_name, read_core_metadata, read_installer,
+    site_packages_dirs, venv_python_version,
+};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// Detector for console scripts installed by pip (`pip install --user`, or
+/// into a virtualenv or Python prefix).
+/// The script's shebang names the interpreter, and the distribution that wrote
+/// the script lists it in its `*.dist-info/RECORD`, so only scripts a
+/// distribution actually owns are reported. Distributions a system package
+/// manager installed (`INSTALLER` of `rpm`, `debian`, ...) are left to it.
+pub struct PipDetector;
+
+/// `INSTALLER` values written by Python installers. Anything else (`rpm`,
+/// `debian`, `conda`) belongs to a system package manager.
+const PYTHON_INSTALLERS: &[&str] = &["pip", "uv", "pipx", "poetry", "pdm", "hatch", "flit"];
+
+impl PipDetector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A distribution that lists the command in its `RECORD`.
+struct Distribution {
+    dist_info: PathBuf,
+    /// Prefix whose `site-packages` holds the distribution (`~/.local`, a venv)
+    prefix: PathBuf,
+    name: String,
+    version: String,
+    /// Size the `RECORD` lists for the script, when it has one
+    script_size: Option<u64>,
+}
+
+impl Distribution {
+    fn installer(&self) -> Option<String> {
+        read_installer(&self.dist_info)
+    }
+
+    /// The prefix, if it is a virtual environment.
+    fn venv(&self) -> Option<&Path> {
+        Some(self.prefix.as_path()).filter(|prefix| prefix.join("pyvenv.cfg").is_file())
+    }
+
+    /// Whether a Python installer wrote the distribution. Distributions without
+    /// an `INSTALLER` file predate it and were installed by pip or setuptools.
+    fn python_installed(&self) -> bool {
+        self.installer().is_none_or(|installer| {
+            let tool = installer.split_whitespace().next().unwrap_or_default();
+            PYTHON_INSTALLERS
+                .iter()
+                .any(|known| tool.eq_ignore_ascii_case(known))
+        })
+    }
+}
+
+fn is_python(interpreter: &Path) -> bool {
+    interpreter.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name.starts_with("python") || name.starts_with("pypy")
+    })
+}
+
+/// Find the distribution that installed the script, looking in the script's
+/// own prefix (`~/.local/bin` -> `~/.local`, a venv's `bin/`) and the prefix of
+/// the interpreter from its shebang.
+fn find_distribution(ctx: &DetectionContext) -> Option<Distribution> {
+    let interpreter = ctx.interpreter().filter(|i| is_python(i))?;
+    let script = ctx.resolved_path.canonicalize().ok()?;
+    let script_name = script.file_name()?;
+
+    let mut prefixes: Vec<&Path> = [&ctx.resolved_path, interpreter]
+        .into_iter()
+        .filter_map(|path| path.parent()?.parent())
+        .collect();
+    prefixes.dedup();
+
+    prefixes.into_iter().find_map(|prefix| {
+        site_packages_dirs(prefix).iter().find_map(|site| {
+            dist_info_dirs(site).into_iter().find_map(|dist_info| {
+                let script_size = record_entry(site, &dist_info, &script, script_name)?;
+                let dir_name = dist_info.file_name()?.to_string_lossy().to_string();
+                let (name, version) = parse_dist_info_name(&dir_name)?;
+                Some(Distribution {
+                    name: read_dist_name(&dist_info).unwrap_or_else(|| name.to_string()),
+                    version: version.to_string(),
+                    prefix: prefix.to_path_buf(),
+                    script_size,
+                    dist_info,
+                })
+                .filter(Distribution::python_installed)
+            })
+        })
+    })
+}
+
+/// Find `script` in a distribution's `RECORD`, returning the size it lists.
+/// Entries are relative to `site-packages` (`../../../bin/black,sha256=...,1234`).
+fn record_entry(
+    site: &Path,
+    dist_info: &Path,
+    script: &Path,
+    script_name: &OsStr,
+) -> Option<Option<u64>> {
+    let record = std::fs::read_to_string(dist_info.join("RECORD")).ok()?;
+    record.lines().find_map(|line| {
+        // CSV: the path is quoted only when it contains a comma
+        let (entry, rest) = match line.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => line.split_once(',').unwrap_or((line, "")),
+        };
+        let entry = Path::new(entry);
+        let owned = entry.file_name() == Some(script_name)
+            && site
+                .join(entry)
+                .canonicalize()
+                .is_ok_and(|path| path == script);
+        owned.then(|| rest.rsplit(',').next().and_then(|size| size.parse().ok()))
+    })
+}
+
+/// The distribution's display name from the `Name` header of `METADATA`.
+fn read_dist_name(dist_info: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dist_info.join("METADATA")).ok()?;
+    content
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .find_map(|line| line.strip_prefix("Name: "))
+        .map(|name| name.trim().to_string())
+}
+
+impl PackageManagerDetector for PipDetector {
+    fn id(&self) -> &'static str {
+        "pip"
+    }
+
+    fn name(&self) -> &str {
+        "pip"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        // Windows console scripts are launcher executables without a shebang
+        matches!(platform, Platform::MacOS | Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        80 // Below pipx and conda, which own their environments
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let dist = find_distribution(ctx)?;
+
+        // Raised to High once the script matches its `RECORD` entry
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: Some(dist.name),
+            version: Some(dist.version),
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(dist) = find_distribution(ctx) else {
+            return Verification::Unverified;
+        };
+        let Some(expected) = dist.script_size else {
+            return Verification::Unverified;
+        };
+        // A different size means something rewrote the script after install
+        let size = std::fs::metadata(&ctx.resolved_path).map(|m| m.len());
+        if size.ok() != Some(expected) {
+            return Verification::Contradicted;
+        }
+
+        // uv, poetry and pdm install the same way; report whichever did
+        if let Some(installer) = dist.installer() {
+            result.manager_name = installer;
+        }
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let dist = find_distribution(ctx)?;
+
+        let mut info = read_core_metadata(&dist.dist_info);
+        info.install_date = modified_time(&dist.dist_info.join("RECORD"));
+        // pip and uv mark distributions the user asked for with `REQUESTED`
+        info.installed_on_request = Some(dist.dist_info.join("REQUESTED").exists());
+        info.provides = entry_point_scripts(&dist.dist_info);
+
+        let details = [
+            ("installer", dist.installer()),
+            (
+                "interpreter",
+                ctx.interpreter().map(|i| i.display().to_string()),
+            ),
+            ("environment", dist.venv().map(|v| v.display().to_string())),
+            ("python_version", dist.venv().and_then(venv_python_version)),
+        ];
+        for (key, value) in details {
+            if let Some(value) = value {
+                info.extra.insert(key.to_string(), value);
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{LinkKind, ScriptLink};
+    use super::*;
+
+    fn make_context(command: &str, script: &Path, interpreter: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: script.to_path_buf(),
+            symlink_chain: vec![script.to_path_buf()],
+            resolved_path: script.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: vec![ScriptLink {
+                kind: LinkKind::InterpretedBy,
+                path: interpreter.to_path_buf(),
+            }],
+        }
+    }
+
+    /// Install a distribution into `prefix` that owns `bin/<script>`.
+    fn install(prefix: &Path, dist_info: &str, script: &str, installer: &str) -> PathBuf {
+        let dist = prefix.join("lib/python3.12/site-packages").join(dist_info);
+        std::fs::create_dir_all(&dist).unwrap();
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        std::fs::write(prefix.join("bin").join(script), "#!python\n").unwrap();
+        std::fs::write(
+            dist.join("RECORD"),
+            format!("../../../bin/{script},sha256=abc,9\n{dist_info}/INSTALLER,,\n"),
+        )
+        .unwrap();
+        std::fs::write(dist.join("INSTALLER"), format!("{installer}\n")).unwrap();
+        dist
+    }
+
+    #[test]
+    fn test_detects_user_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join(".local");
+        let dist = install(&local, "black-24.1.0.dist-info", "black", "pip");
+        std::fs::write(
+            dist.join("METADATA"),
+            "Name: black\nSummary: The uncompromising code formatter.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dist.join("entry_points.txt"),
+            "[console_scripts]\nblack = black:patched_main\nblackd = blackd:patched_main [d]\n",
+        )
+        .unwrap();
+        std::fs::write(dist.join("REQUESTED"), "").unwrap();
+
+        let ctx = make_context(
+            "black",
+            &local.join("bin/black"),
+            Path::new("/usr/bin/python3"),
+        );
+        let detector = PipDetector::new();
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "pip");
+        assert_eq!(result.manager_name, "pip");
+        assert_eq!(result.package_name, Some("black".to_string()));
+        assert_eq!(result.version, Some("24.1.0".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("The uncompromising code formatter.")
+        );
+        assert_eq!(info.provides, vec!["black", "blackd"]);
+        assert_eq!(info.installed_on_request, Some(true));
+        assert_eq!(info.extra["interpreter"], "/usr/bin/python3");
+        assert!(!info.extra.contains_key("environment"));
+    }
+
+    #[test]
+    fn test_detects_venv_script_and_installer() {
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join("venvs/api");
+        install(&venv, "httpx-0.27.0.dist-info", "httpx", "uv");
+        std::fs::write(
+            venv.join("pyvenv.cfg"),
+            "home = /usr/bin\nimplementation = CPython\nversion_info = 3.12.1\n",
+        )
+        .unwrap();
+
+        let ctx = make_context("httpx", &venv.join("bin/httpx"), &venv.join("bin/python"));
+        let detector = PipDetector::new();
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "pip");
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.manager_name, "uv");
+        assert_eq!(result.package_name, Some("httpx".to_string()));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.installed_on_request, Some(false));
+        assert_eq!(info.extra["installer"], "uv");
+        assert_eq!(info.extra["environment"], venv.display().to_string());
+        assert_eq!(info.extra["python_version"], "3.12.1");
+    }
+
+    #[test]
+    fn test_rewritten_script_is_contradicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join(".local");
+        install(&local, "black-24.1.0.dist-info", "black", "pip");
+        std::fs::write(local.join("bin/black"), "#!/usr/bin/python3\n# patched\n").unwrap();
+
+        let ctx = make_context(
+            "black",
+            &local.join("bin/black"),
+            Path::new("/usr/bin/python3"),
+        );
+        let detector = PipDetector::new();
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(
+            detector.verify(&ctx, &mut result),
+            Verification::Contradicted
+        );
+    }
+
+    #[test]
+    fn test_leaves_system_packages_to_their_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        let usr = dir.path().join("usr");
+        install(&usr, "dnf-4.18.0.dist-info", "dnf", "rpm");
+
+        let ctx = make_context("dnf", &usr.join("bin/dnf"), Path::new("/usr/bin/python3"));
+        assert!(PipDetector::new().detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_ignores_scripts_no_distribution_owns() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join(".local");
+        install(&local, "black-24.1.0.dist-info", "black", "pip");
+        std::fs::write(local.join("bin/my-script"), "#!/usr/bin/python3\n").unwrap();
+
+        let ctx = make_context(
+            "my-script",
+            &local.join("bin/my-script"),
+            Path::new("/usr/bin/python3"),
+        );
+        assert!(PipDetector::new().detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_requires_python_interpreter() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join(".local");
+        install(&local, "black-24.1.0.dist-info", "black", "pip");
+
+        let script = local.join("bin/black");
+        assert!(PipDetector::new()
+            .detect(&make_context("black", &script, Path::new("/bin/sh")))
+            .is_none());
+
+        let mut ctx = make_context("black", &script, Path::new("/usr/bin/python3"));
+        ctx.script_links.clear();
+        assert!(PipDetector::new().detect(&ctx).is_none());
+    }
+}
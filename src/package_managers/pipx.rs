@@ -1,9 +1,15 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::info::modified_time;
+use super::python::{find_dist_info, read_core_metadata, site_packages_dirs};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use std::path::{Path, PathBuf};
 
 /// Detector for pipx installed packages.
-/// Note: We only detect pipx (not pip install --user) to avoid false positives,
-/// since ~/.local/bin is used by many other tools.
+/// Note: ~/.local/bin is used by many other tools, so it is not matched here;
+/// `PipDetector` claims scripts there only when a distribution's RECORD lists them.
 pub struct PipxDetector;
 
 impl PipxDetector {
@@ -30,7 +36,7 @@ impl PackageManagerDetector for PipxDetector {
     }
 
     fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
-        for path in &ctx.symlink_chain {
+        for path in ctx.candidate_paths() {
             let path_str = path.to_string_lossy();
 
             // Check for pipx paths:
@@ -43,7 +49,10 @@ impl PackageManagerDetector for PipxDetector {
                     manager_name: self.name().to_string(),
                     package_name,
                     version: None,
+                    details: Default::default(),
                     confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
@@ -52,6 +61,105 @@ impl PackageManagerDetector for PipxDetector {
 
         None
     }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(venv) = ctx.candidate_paths().find_map(|p| find_pipx_venv(p)) else {
+            return Verification::Unverified;
+        };
+        verify_pipx_metadata(&venv, ctx, result)
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let venv = ctx.candidate_paths().find_map(|p| find_pipx_venv(p))?;
+        read_pipx_info(&venv)
+    }
+}
+
+/// Find the `pipx/venvs/<package>` directory containing `path`.
+fn find_pipx_venv(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| {
+            let mut parents = dir.ancestors().skip(1).map(|p| p.file_name());
+            parents.next() == Some(Some("venvs".as_ref()))
+                && parents.next() == Some(Some("pipx".as_ref()))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// pipx records every venv it manages in `pipx_metadata.json`, including the
+/// apps each package exposes. The command must be one of them: the main
+/// package's, or an injected package's.
+fn verify_pipx_metadata(
+    venv: &Path,
+    ctx: &DetectionContext,
+    result: &mut DetectionResult,
+) -> Verification {
+    let Some(metadata) = std::fs::read_to_string(venv.join("pipx_metadata.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else {
+        return Verification::Unverified;
+    };
+
+    // The command as named inside the venv (`http`, or `http.exe` on Windows)
+    let names: Vec<String> = ctx
+        .command_files()
+        .filter(|p| p.starts_with(venv))
+        .filter_map(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .chain([ctx.command_name.clone()])
+        .collect();
+    let provides = |package: &serde_json::Value| {
+        package["apps"].as_array().is_some_and(|apps| {
+            apps.iter()
+                .any(|a| a.as_str().is_some_and(|a| names.iter().any(|n| n == a)))
+        })
+    };
+
+    let main_package = &metadata["main_package"];
+    let injected = metadata["injected_packages"].as_object();
+    let owner = if provides(main_package) {
+        main_package
+    } else if let Some(package) = injected.and_then(|i| i.values().find(|p| provides(p))) {
+        package
+    } else {
+        return Verification::Contradicted;
+    };
+
+    if let Some(package) = owner["package"].as_str() {
+        result.package_name = Some(package.to_string());
+    }
+    if let Some(version) = owner["package_version"].as_str() {
+        result.version = Some(version.to_string());
+    }
+    Verification::Verified
+}
+
+fn read_pipx_info(venv: &Path) -> Option<PackageInfo> {
+    let metadata_path = venv.join("pipx_metadata.json");
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&metadata_path).ok()?).ok()?;
+    let main_package = &metadata["main_package"];
+    let package = main_package["package"].as_str()?;
+
+    let mut info = site_packages_dirs(venv)
+        .iter()
+        .find_map(|site| find_dist_info(site, package))
+        .map(|dist_info| read_core_metadata(&dist_info))
+        .unwrap_or_default();
+
+    info.install_date = modified_time(&metadata_path);
+    info.installed_on_request = Some(true);
+    info.provides = main_package["apps"]
+        .as_array()
+        .map(|apps| {
+            apps.iter()
+                .filter_map(|a| a.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    info.provides.sort();
+    Some(info)
 }
 
 fn extract_pipx_package_name(path: &str) -> Option<String> {
@@ -79,8 +187,8 @@ fn extract_pipx_package_name(path: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use super::super::{LinkKind, ScriptLink};
     use super::*;
-    use std::path::PathBuf;
 
     fn make_context(command: &str, paths: Vec<&str>, platform: Platform) -> DetectionContext {
         let command_path = PathBuf::from(paths.first().unwrap_or(&""));
@@ -91,6 +199,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -152,6 +261,22 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_detects_copied_script_by_interpreter() {
+        // A copy (not a symlink) in ~/.local/bin still runs the venv's Python
+        let mut ctx = make_context(
+            "black",
+            vec!["/home/user/.local/bin/black"],
+            Platform::Linux,
+        );
+        ctx.script_links = vec![ScriptLink {
+            kind: LinkKind::InterpretedBy,
+            path: PathBuf::from("/home/user/.local/pipx/venvs/black/bin/python"),
+        }];
+        let result = PipxDetector::new().detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("black".to_string()));
+    }
+
     #[test]
     fn test_ignores_non_pipx_paths() {
         let detector = PipxDetector::new();
@@ -160,6 +285,97 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_verify_reads_pipx_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join("pipx/venvs/httpie");
+        std::fs::create_dir_all(venv.join("bin")).unwrap();
+        std::fs::write(
+            venv.join("pipx_metadata.json"),
+            r#"{"main_package": {"package": "httpie", "package_version": "3.2.2", "apps": ["http", "https"]},
+                "injected_packages": {"httpie-jwt-auth": {"package": "httpie-jwt-auth",
+                    "package_version": "0.4.0", "apps": ["jwt-auth"]}}}"#,
+        )
+        .unwrap();
+        let bin = venv.join("bin/http");
+
+        let detector = PipxDetector::new();
+        let ctx = make_context("http", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("httpie".to_string()));
+        assert_eq!(result.version, Some("3.2.2".to_string()));
+
+        // Apps of injected packages are linked into the same venv
+        let bin = venv.join("bin/jwt-auth");
+        let ctx = make_context("jwt-auth", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+        assert_eq!(result.package_name, Some("httpie-jwt-auth".to_string()));
+        assert_eq!(result.version, Some("0.4.0".to_string()));
+    }
+
+    #[test]
+    fn test_verify_contradicted_by_apps() {
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join("pipx/venvs/httpie");
+        std::fs::create_dir_all(venv.join("bin")).unwrap();
+        std::fs::write(
+            venv.join("pipx_metadata.json"),
+            r#"{"main_package": {"package": "httpie", "package_version": "3.2.2", "apps": ["http", "https"]}}"#,
+        )
+        .unwrap();
+        // A dependency's script in the venv bin dir, not an app pipx exposes
+        let bin = venv.join("bin/normalizer");
+
+        let detector = PipxDetector::new();
+        let ctx = make_context("normalizer", vec![bin.to_str().unwrap()], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(
+            detector.verify(&ctx, &mut result),
+            Verification::Contradicted
+        );
+        assert_eq!(result.version, None);
+    }
+
+    #[test]
+    fn test_read_pipx_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join("pipx/venvs/httpie");
+        let dist = venv.join("lib/python3.12/site-packages/httpie-3.2.2.dist-info");
+        std::fs::create_dir_all(&dist).unwrap();
+        std::fs::write(
+            venv.join("pipx_metadata.json"),
+            r#"{"main_package": {"package": "httpie", "apps": ["https", "http", "httpie"]}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dist.join("METADATA"),
+            "Name: httpie\nSummary: HTTPie: modern, user-friendly command-line HTTP client\n",
+        )
+        .unwrap();
+
+        let info = read_pipx_info(&venv).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("HTTPie: modern, user-friendly command-line HTTP client")
+        );
+        assert_eq!(info.provides, vec!["http", "httpie", "https"]);
+        assert_eq!(info.installed_on_request, Some(true));
+    }
+
+    #[test]
+    fn test_verify_without_metadata_is_unverified() {
+        let detector = PipxDetector::new();
+        let ctx = make_context(
+            "http",
+            vec!["/nonexistent/.local/pipx/venvs/httpie/bin/http"],
+            Platform::Linux,
+        );
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
     #[test]
     fn test_supports_all_platforms() {
         let detector = PipxDetector::new();
@@ -1,5 +1,5 @@
-use super::node_global::{detect_node_global, SKIP_BIN_PNPM};
-use super::{DetectionContext, DetectionResult, PackageManagerDetector};
+use super::node_global::{detect_node_global, node_global_info, verify_node_global, SKIP_BIN_PNPM};
+use super::{DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for pnpm global packages.
@@ -37,6 +37,14 @@ impl PackageManagerDetector for PnpmGlobalDetector {
             SKIP_BIN_PNPM,
         )
     }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        verify_node_global(ctx, result)
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        node_global_info(ctx, result)
+    }
 }
 
 fn is_pnpm_global_path(path: &str) -> bool {
@@ -65,6 +73,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -0,0 +1,186 @@
+//! Helpers for reading installed Python distributions (`*.dist-info`),
+//! shared by the Python-ecosystem detectors.
+
+use super::PackageInfo;
+use std::path::{Path, PathBuf};
+
+/// Normalize a distribution name per PEP 503 (lowercase, runs of `-_.` → `-`).
+pub(super) fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut last_was_separator = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_was_separator {
+                normalized.push('-');
+            }
+            last_was_separator = true;
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+            last_was_separator = false;
+        }
+    }
+    normalized
+}
+
+/// Find the `site-packages` directories of a Python prefix (venv or install root).
+/// Unix: `{prefix}/lib/python3.X/site-packages` (`dist-packages` on Debian)
+/// Windows: `{prefix}\Lib\site-packages`
+pub(super) fn site_packages_dirs(prefix: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for lib in ["lib", "Lib"] {
+        let lib_dir = prefix.join(lib);
+        let direct = lib_dir.join("site-packages");
+        if direct.is_dir() {
+            dirs.push(direct);
+        }
+        if let Ok(entries) = std::fs::read_dir(&lib_dir) {
+            let mut versioned: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with("python"))
+                .flat_map(|e| ["site-packages", "dist-packages"].map(|d| e.path().join(d)))
+                .filter(|p| p.is_dir())
+                .collect();
+            versioned.sort();
+            dirs.extend(versioned);
+        }
+    }
+    dirs.dedup();
+    dirs
+}
+
+/// Split a `{name}-{version}.dist-info` directory name into its parts.
+pub(super) fn parse_dist_info_name(dir_name: &str) -> Option<(&str, &str)> {
+    dir_name.strip_suffix(".dist-info")?.rsplit_once('-')
+}
+
+/// All `.dist-info` directories in a `site-packages` directory, sorted.
+pub(super) fn dist_info_dirs(site_packages: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(site_packages) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".dist-info"))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// The tool that installed a distribution (`pip`, `uv`, `poetry`, ...), from
+/// its `INSTALLER` file.
+pub(super) fn read_installer(dist_info: &Path) -> Option<String> {
+    let installer = std::fs::read_to_string(dist_info.join("INSTALLER")).ok()?;
+    Some(installer.trim().to_string()).filter(|i| !i.is_empty())
+}
+
+/// Names of the `console_scripts` and `gui_scripts` entry points of a
+/// distribution, sorted.
+pub(super) fn entry_point_scripts(dist_info: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(dist_info.join("entry_points.txt")) else {
+        return Vec::new();
+    };
+    let mut scripts = Vec::new();
+    let mut in_scripts = false;
+    for line in content.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_scripts = matches!(section.trim(), "console_scripts" | "gui_scripts");
+        } else if in_scripts {
+            if let Some((name, _)) = line.split_once('=') {
+                scripts.push(name.trim().to_string());
+            }
+        }
+    }
+    scripts.sort();
+    scripts
+}
+
+/// Python version of a virtual environment, from its `pyvenv.cfg`.
+pub(super) fn venv_python_version(venv: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(venv.join("pyvenv.cfg")).ok()?;
+    // `version` from venv/virtualenv, `version_info` from uv and newer virtualenv
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "version" | "version_info").then(|| value.trim().to_string())
+    })
+}
+
+/// Find the `.dist-info` directory of `package` in a `site-packages` directory.
+pub(super) fn find_dist_info(site_packages: &Path, package: &str) -> Option<PathBuf> {
+    let wanted = normalize_name(package);
+    std::fs::read_dir(site_packages)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .find(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            parse_dist_info_name(&name).is_some_and(|(dist, _)| normalize_name(dist) == wanted)
+        })
+        .map(|e| e.path())
+}
+
+/// Read summary, homepage and license from a distribution's `METADATA` file.
+pub(super) fn read_core_metadata(dist_info: &Path) -> PackageInfo {
+    let mut info = PackageInfo::default();
+    let Ok(content) = std::fs::read_to_string(dist_info.join("METADATA")) else {
+        return info;
+    };
+
+    // Headers end at the first blank line; the long description follows
+    for line in content.lines().take_while(|l| !l.is_empty()) {
+        let Some((key, value)) = line.split_once(": ") else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key {
+            "Summary" => info.description = Some(value),
+            "Home-page" => info.homepage = Some(value),
+            "Project-URL" if info.homepage.is_none() => {
+                if let Some((label, url)) = value.split_once(", ") {
+                    if label.eq_ignore_ascii_case("homepage") {
+                        info.homepage = Some(url.to_string());
+                    }
+                }
+            }
+            "License-Expression" => info.license = Some(value),
+            "License" if info.license.is_none() => info.license = Some(value),
+            _ => {}
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(
+            normalize_name("Django_REST.framework"),
+            "django-rest-framework"
+        );
+        assert_eq!(normalize_name("ruamel.yaml"), "ruamel-yaml");
+    }
+
+    #[test]
+    fn finds_dist_info_and_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("lib/python3.12/site-packages");
+        let dist = site.join("ruamel_yaml-0.18.5.dist-info");
+        std::fs::create_dir_all(&dist).unwrap();
+        std::fs::write(
+            dist.join("METADATA"),
+            "Metadata-Version: 2.1\nName: ruamel.yaml\nSummary: YAML parser\n\
+             Project-URL: Homepage, https://example.com\nLicense: MIT\n\nLong text\n",
+        )
+        .unwrap();
+
+        assert_eq!(site_packages_dirs(dir.path()), vec![site.clone()]);
+        assert_eq!(find_dist_info(&site, "ruamel.yaml"), Some(dist.clone()));
+
+        let info = read_core_metadata(&dist);
+        assert_eq!(info.description.as_deref(), Some("YAML parser"));
+        assert_eq!(info.homepage.as_deref(), Some("https://example.com"));
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+    }
+}
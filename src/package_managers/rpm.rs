@@ -0,0 +1,307 @@
+use super::info::format_timestamp;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Query format for `rpm -qf`: one tab-separated line per owning package.
+const OWNER_QUERY_FORMAT: &str = "%{NAME}\\t%{VERSION}-%{RELEASE}\\t%{ARCH}\\n";
+
+/// Query format for `--info`: summary, URL, license and install time.
+const INFO_QUERY_FORMAT: &str = "%{SUMMARY}\\t%{URL}\\t%{LICENSE}\\t%{INSTALLTIME}\\n";
+
+/// Front-end package managers layered on top of rpm, in lookup order.
+const FRONTENDS: &[(&str, &str)] = &[
+    ("usr/bin/zypper", "zypper"),
+    ("usr/bin/dnf5", "dnf"),
+    ("usr/bin/dnf", "dnf"),
+    ("usr/bin/yum", "yum"),
+];
+
+/// Detector for RPM packages (Fedora, RHEL, openSUSE).
+/// Claims system paths when `rpm` is installed; verification asks `rpm -qf`
+/// which package owns the file.
+pub struct RpmDetector {
+    root: PathBuf,
+}
+
+impl RpmDetector {
+    pub fn new() -> Self {
+        Self::with_root(Path::new("/"))
+    }
+
+    /// Look for front-end tools below `root` instead of the running system's.
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Display name including the front-end (e.g. "RPM (dnf)") when one is installed.
+    fn display_name(&self) -> String {
+        FRONTENDS
+            .iter()
+            .find(|(path, _)| self.root.join(path).exists())
+            .map(|(_, frontend)| format!("RPM ({})", frontend))
+            .unwrap_or_else(|| self.name().to_string())
+    }
+}
+
+/// A package reported by `rpm -qf`.
+#[derive(Debug, PartialEq)]
+struct RpmPackage {
+    name: String,
+    /// `VERSION-RELEASE`
+    version: String,
+    arch: String,
+}
+
+impl PackageManagerDetector for RpmDetector {
+    fn id(&self) -> &'static str {
+        "rpm"
+    }
+
+    fn name(&self) -> &str {
+        "RPM"
+    }
+
+    fn supports_platform(&self, platform: Platform) -> bool {
+        matches!(platform, Platform::Linux)
+    }
+
+    fn priority(&self) -> i32 {
+        50 // Same level as apt
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let path_str = ctx.resolved_path.to_string_lossy();
+
+        // Only check system paths
+        if !path_str.starts_with("/usr/bin/")
+            && !path_str.starts_with("/usr/sbin/")
+            && !path_str.starts_with("/bin/")
+            && !path_str.starts_with("/sbin/")
+        {
+            return None;
+        }
+        which::which("rpm").ok()?;
+
+        // The owning package comes from `rpm -qf` during verification
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.display_name(),
+            package_name: None,
+            version: None,
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        match query_owner(&ctx.resolved_path) {
+            Some(Some(package)) => {
+                result.package_name = Some(package.name);
+                result.version = Some(package.version);
+                result
+                    .details
+                    .insert("architecture".to_string(), package.arch);
+                Verification::Verified
+            }
+            Some(None) => Verification::Contradicted,
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, _ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let name = result.package_name.as_deref()?;
+        // `name.arch` picks one package when several architectures are installed
+        let package = match result.details.get("architecture") {
+            Some(arch) => format!("{name}.{arch}"),
+            None => name.to_string(),
+        };
+
+        let output = Command::new("rpm")
+            .args(["-q", "--queryformat", INFO_QUERY_FORMAT, &package])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let mut info = parse_info_output(&String::from_utf8_lossy(&output.stdout))?;
+
+        if let Ok(output) = Command::new("rpm").args(["-ql", &package]).output() {
+            if output.status.success() {
+                info.provides = bin_commands(&String::from_utf8_lossy(&output.stdout));
+            }
+        }
+
+        Some(info)
+    }
+}
+
+/// Run `rpm -qf` for a file and return its owning package: `None` when rpm
+/// could not be run, `Some(None)` when no package owns the file.
+fn query_owner(path: &Path) -> Option<Option<RpmPackage>> {
+    let output = Command::new("rpm")
+        .arg("-qf")
+        .args(["--queryformat", OWNER_QUERY_FORMAT])
+        .arg(path)
+        .output()
+        .ok()?;
+
+    // rpm exits non-zero for files not owned by any package
+    if !output.status.success() {
+        return Some(None);
+    }
+
+    Some(parse_owner_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `NAME\tVERSION-RELEASE\tARCH` lines; the first owner wins.
+fn parse_owner_output(output: &str) -> Option<RpmPackage> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split('\t');
+        let name = fields.next()?.trim();
+        let version = fields.next()?.trim();
+        let arch = fields.next()?.trim();
+        if name.is_empty() || version.is_empty() {
+            return None;
+        }
+        Some(RpmPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            arch: arch.to_string(),
+        })
+    })
+}
+
+/// Parse the `INFO_QUERY_FORMAT` line. rpm prints `(none)` for missing tags.
+fn parse_info_output(output: &str) -> Option<PackageInfo> {
+    let line = output.lines().next()?;
+    let mut fields = line
+        .split('\t')
+        .map(|f| Some(f.trim()).filter(|f| !f.is_empty() && *f != "(none)"));
+
+    let description = fields.next().flatten().map(String::from);
+    let homepage = fields.next().flatten().map(String::from);
+    let license = fields.next().flatten().map(String::from);
+    let install_date = fields
+        .next()
+        .flatten()
+        .and_then(|t| t.parse::<u64>().ok())
+        .map(format_timestamp);
+
+    Some(PackageInfo {
+        description,
+        homepage,
+        license,
+        install_date,
+        ..Default::default()
+    })
+}
+
+/// Commands installed into a bin directory, from `rpm -ql` output.
+fn bin_commands(file_list: &str) -> Vec<String> {
+    let mut commands: Vec<String> = file_list
+        .lines()
+        .map(Path::new)
+        .filter(|p| {
+            p.parent()
+                .and_then(|d| d.file_name())
+                .is_some_and(|d| d == "bin" || d == "sbin")
+        })
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    commands.sort();
+    commands.dedup();
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &str) -> DetectionContext {
+        let path = PathBuf::from(path);
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.clone(),
+            symlink_chain: vec![path.clone()],
+            resolved_path: path,
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_owner_output() {
+        let package = parse_owner_output("coreutils\t9.3-4.fc39\tx86_64\n").unwrap();
+        assert_eq!(
+            package,
+            RpmPackage {
+                name: "coreutils".to_string(),
+                version: "9.3-4.fc39".to_string(),
+                arch: "x86_64".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_owner_output_not_owned() {
+        assert!(parse_owner_output("file /usr/bin/foo is not owned by any package\n").is_none());
+        assert!(parse_owner_output("").is_none());
+    }
+
+    #[test]
+    fn test_parse_info_output() {
+        let info = parse_info_output(
+            "Fast distributed version control system\thttps://git-scm.com/\tGPL-2.0-only\t1700000000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Fast distributed version control system")
+        );
+        assert_eq!(info.homepage.as_deref(), Some("https://git-scm.com/"));
+        assert_eq!(info.license.as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(info.install_date.as_deref(), Some("2023-11-14T22:13:20Z"));
+    }
+
+    #[test]
+    fn test_parse_info_output_none_tags() {
+        let info = parse_info_output("Tool\t(none)\tMIT\t(none)\n").unwrap();
+        assert_eq!(info.homepage, None);
+        assert_eq!(info.install_date, None);
+    }
+
+    #[test]
+    fn test_bin_commands() {
+        let files = "/usr/bin/git\n/usr/bin/git-shell\n/usr/libexec/git-core/git\n/usr/share/man/man1/git.1.gz\n";
+        assert_eq!(bin_commands(files), vec!["git", "git-shell"]);
+    }
+
+    #[test]
+    fn test_display_name_labels_frontend() {
+        let root = tempfile::tempdir().unwrap();
+        let detector = RpmDetector::with_root(root.path());
+        assert_eq!(detector.display_name(), "RPM");
+
+        std::fs::create_dir_all(root.path().join("usr/bin")).unwrap();
+        std::fs::write(root.path().join("usr/bin/dnf"), "").unwrap();
+        assert_eq!(detector.display_name(), "RPM (dnf)");
+    }
+
+    #[test]
+    fn test_ignores_non_system_paths() {
+        let detector = RpmDetector::new();
+        let ctx = make_context("rg", "/home/user/.cargo/bin/rg");
+        assert!(detector.detect(&ctx).is_none());
+    }
+}
@@ -0,0 +1,575 @@
+//! Toolchain resolution for rustup proxies. `cargo`, `rustc` and friends in
+//! `$CARGO_HOME/bin` are all the `rustup` binary (hard links or symlinks),
+//! which runs the same-named tool from the active toolchain under
+//! `$RUSTUP_HOME/toolchains/<toolchain>/bin`.
+//!
+//! The active toolchain is worked out the way rustup does it, without
+//! executing the proxy.
+
+use super::info::{list_commands, modified_time};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use std::path::{Path, PathBuf};
+
+/// Commands rustup installs as proxies.
+pub(super) const PROXIES: &[&str] = &[
+    "cargo",
+    "cargo-clippy",
+    "cargo-fmt",
+    "cargo-miri",
+    "clippy-driver",
+    "rls",
+    "rust-analyzer",
+    "rust-gdb",
+    "rust-gdbgui",
+    "rust-lldb",
+    "rustc",
+    "rustdoc",
+    "rustfmt",
+];
+
+/// Detector for rustup toolchain proxies.
+pub struct RustupDetector {
+    /// `RUSTUP_HOME`, or `~/.rustup`
+    rustup_home: Option<PathBuf>,
+    /// `CARGO_HOME`, or `~/.cargo`
+    cargo_home: Option<PathBuf>,
+    /// `RUSTUP_TOOLCHAIN`
+    toolchain_override: Option<String>,
+    cwd: Option<PathBuf>,
+}
+
+/// The toolchain a proxy runs, and what selected it.
+#[derive(Debug, Clone, PartialEq)]
+struct ActiveToolchain {
+    name: String,
+    origin: String,
+}
+
+impl RustupDetector {
+    pub fn new() -> Self {
+        let home_dir = |var: &str, default: &str| {
+            std::env::var_os(var)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| dirs::home_dir().map(|home| home.join(default)))
+        };
+        Self {
+            rustup_home: home_dir("RUSTUP_HOME", ".rustup"),
+            cargo_home: home_dir("CARGO_HOME", ".cargo"),
+            toolchain_override: std::env::var("RUSTUP_TOOLCHAIN")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    /// Whether `path` in `$CARGO_HOME/bin` is a rustup proxy: a link to the
+    /// `rustup` binary, or one of the names rustup installs.
+    fn is_proxy(&self, bin: &Path, ctx: &DetectionContext, path: &Path) -> bool {
+        let Some(name) = path.file_stem().map(|n| n.to_string_lossy()) else {
+            return false;
+        };
+        if PROXIES.contains(&name.as_ref()) {
+            return true;
+        }
+        let rustup = bin.join(format!("rustup{}", std::env::consts::EXE_SUFFIX));
+        ctx.symlink_chain.iter().any(|p| p == &rustup) || same_file(path, &rustup)
+    }
+
+    /// The active toolchain: `RUSTUP_TOOLCHAIN`, then the nearest directory
+    /// override or toolchain file, then the default toolchain.
+    fn active_toolchain(&self, settings: &toml::Table) -> Option<ActiveToolchain> {
+        if let Some(ref name) = self.toolchain_override {
+            return Some(ActiveToolchain {
+                name: name.clone(),
+                origin: "RUSTUP_TOOLCHAIN".to_string(),
+            });
+        }
+
+        let overrides = settings.get("overrides").and_then(|o| o.as_table());
+        if let Some(ref cwd) = self.cwd {
+            for dir in cwd.ancestors() {
+                // `rustup override set` entries are keyed by directory
+                if let Some(name) = overrides
+                    .and_then(|o| o.get(dir.to_string_lossy().as_ref()))
+                    .and_then(|v| v.as_str())
+                {
+                    return Some(ActiveToolchain {
+                        name: name.to_string(),
+                        origin: format!("override for {}", dir.display()),
+                    });
+                }
+
+                // rustup prefers the legacy `rust-toolchain` name when both exist
+                for file_name in ["rust-toolchain", "rust-toolchain.toml"] {
+                    let file = dir.join(file_name);
+                    if let Some(name) = read_toolchain_file(&file) {
+                        return Some(ActiveToolchain {
+                            name,
+                            origin: file.display().to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let name = settings.get("default_toolchain")?.as_str()?;
+        Some(ActiveToolchain {
+            name: name.to_string(),
+            origin: "default toolchain".to_string(),
+        })
+    }
+
+    fn result(
+        &self,
+        ctx: &DetectionContext,
+        toolchain: Option<String>,
+        version: Option<String>,
+        resolved_path: PathBuf,
+        confidence: Confidence,
+    ) -> DetectionResult {
+        DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            package_name: toolchain,
+            version,
+            details: Default::default(),
+            confidence,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path,
+        }
+    }
+}
+
+/// Whether two paths are hard links to the same file.
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+fn read_settings(rustup_home: &Path) -> toml::Table {
+    std::fs::read_to_string(rustup_home.join("settings.toml"))
+        .ok()
+        .and_then(|content| content.parse().ok())
+        .unwrap_or_default()
+}
+
+/// The channel named by a `rust-toolchain.toml` (`[toolchain] channel` or
+/// `path`) or a legacy one-line `rust-toolchain` file.
+fn read_toolchain_file(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    if let Ok(table) = content.parse::<toml::Table>() {
+        let toolchain = table.get("toolchain")?;
+        return toolchain
+            .get("channel")
+            .or_else(|| toolchain.get("path"))?
+            .as_str()
+            .map(str::to_string);
+    }
+    let line = content.lines().next()?.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Map a toolchain name to its directory. Short names (`stable`, `1.75`)
+/// are installed with the host triple appended.
+fn toolchain_dir(rustup_home: &Path, name: &str, settings: &toml::Table) -> Option<PathBuf> {
+    let toolchains = rustup_home.join("toolchains");
+    // Custom toolchains given as a path in rust-toolchain.toml
+    if Path::new(name).is_absolute() {
+        return Some(PathBuf::from(name));
+    }
+    let exact = toolchains.join(name);
+    if exact.is_dir() {
+        return Some(exact);
+    }
+
+    if let Some(host) = settings.get("default_host_triple").and_then(|h| h.as_str()) {
+        let with_host = toolchains.join(format!("{name}-{host}"));
+        if with_host.is_dir() {
+            return Some(with_host);
+        }
+    }
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(&toolchains)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(name))
+                .is_some_and(|rest| rest.starts_with('-') && rest.matches('-').count() >= 2)
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// `rustc` version from the toolchain's channel manifest (`1.75.0`), when
+/// it was installed from a channel.
+fn toolchain_version(toolchain_dir: &Path) -> Option<String> {
+    let manifest =
+        std::fs::read_to_string(toolchain_dir.join("lib/rustlib/multirust-channel-manifest.toml"))
+            .ok()?;
+    let mut lines = manifest.lines().skip_while(|line| *line != "[pkg.rust]");
+    lines.next()?;
+    let version = lines
+        .take_while(|line| !line.starts_with('['))
+        .find_map(|line| line.strip_prefix("version = "))?;
+    Some(
+        version
+            .trim_matches('"')
+            .split_whitespace()
+            .next()?
+            .to_string(),
+    )
+}
+
+impl PackageManagerDetector for RustupDetector {
+    fn id(&self) -> &'static str {
+        "rustup"
+    }
+
+    fn name(&self) -> &str {
+        "rustup"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true // rustup is cross-platform
+    }
+
+    fn priority(&self) -> i32 {
+        90 // Ahead of Cargo (85), which would otherwise claim the proxies
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let rustup_home = self.rustup_home.as_ref()?;
+        let toolchains = rustup_home.join("toolchains");
+
+        // Results stay Medium until verification finds the toolchain installed.
+        // Called directly as toolchains/<toolchain>/bin/<command>
+        for path in &ctx.symlink_chain {
+            if let Ok(relative) = path.strip_prefix(&toolchains) {
+                let name = relative.iter().next()?.to_string_lossy().to_string();
+                return Some(self.result(
+                    ctx,
+                    Some(name.clone()),
+                    toolchain_version(&toolchains.join(&name)),
+                    ctx.resolved_path.clone(),
+                    Confidence::Medium,
+                ));
+            }
+        }
+
+        let bin = self.cargo_home.as_ref()?.join("bin");
+        let proxy = ctx.symlink_chain.first()?;
+        if proxy.parent() != Some(bin.as_path()) || !self.is_proxy(&bin, ctx, proxy) {
+            return None;
+        }
+        let file_name = proxy.file_name()?;
+        if proxy.file_stem().is_some_and(|stem| stem == "rustup") {
+            return Some(self.result(
+                ctx,
+                Some("rustup".to_string()),
+                None,
+                ctx.resolved_path.clone(),
+                Confidence::Medium,
+            ));
+        }
+
+        let settings = read_settings(rustup_home);
+        let Some(active) = self.active_toolchain(&settings) else {
+            return Some(self.result(
+                ctx,
+                None,
+                None,
+                ctx.resolved_path.clone(),
+                Confidence::Medium,
+            ));
+        };
+        match toolchain_dir(rustup_home, &active.name, &settings) {
+            Some(dir) if dir.join("bin").join(file_name).is_file() => Some(self.result(
+                ctx,
+                dir.file_name().map(|n| n.to_string_lossy().to_string()),
+                toolchain_version(&dir),
+                dir.join("bin").join(file_name),
+                Confidence::Medium,
+            )),
+            // Not installed, or the toolchain lacks this component
+            _ => Some(self.result(
+                ctx,
+                Some(active.name),
+                None,
+                ctx.resolved_path.clone(),
+                Confidence::Medium,
+            )),
+        }
+    }
+
+    fn verify(&self, _ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let (Some(rustup_home), Some(name)) =
+            (self.rustup_home.as_ref(), result.package_name.as_ref())
+        else {
+            return Verification::Unverified;
+        };
+
+        // The rustup binary itself: rustup has been initialised
+        if name == "rustup" {
+            return if rustup_home.join("settings.toml").is_file() {
+                Verification::Verified
+            } else {
+                Verification::Unverified
+            };
+        }
+
+        let toolchain = rustup_home.join("toolchains").join(name);
+        if toolchain.join("lib/rustlib").is_dir()
+            && result.resolved_path.starts_with(&toolchain)
+            && result.resolved_path.is_file()
+        {
+            Verification::Verified
+        } else {
+            Verification::Unverified
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let rustup_home = self.rustup_home.as_ref()?;
+        let toolchain = rustup_home
+            .join("toolchains")
+            .join(result.package_name.as_ref()?);
+        if !toolchain.is_dir() {
+            return None;
+        }
+
+        let mut info = PackageInfo {
+            install_date: modified_time(&toolchain),
+            provides: list_commands(&toolchain.join("bin")),
+            ..Default::default()
+        };
+        let via_proxy = !ctx.symlink_chain.iter().any(|p| p.starts_with(rustup_home));
+        if via_proxy {
+            if let Some(active) = self.active_toolchain(&read_settings(rustup_home)) {
+                info.extra.insert("selected_by".to_string(), active.origin);
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&Path>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    const HOST: &str = "x86_64-unknown-linux-gnu";
+
+    /// Stable and nightly toolchains, stable as the default.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (channel, version) in [("stable", "1.75.0"), ("nightly", "1.77.0-nightly")] {
+            let toolchain = dir
+                .path()
+                .join(".rustup/toolchains")
+                .join(format!("{channel}-{HOST}"));
+            write(&toolchain.join("bin/cargo"), "");
+            write(&toolchain.join("bin/rustc"), "");
+            write(
+                &toolchain.join("lib/rustlib/multirust-channel-manifest.toml"),
+                &format!(
+                    "manifest-version = \"2\"\n\n[pkg.cargo]\nversion = \"0.76.0 (abc 2023-12-01)\"\n\n\
+                     [pkg.rust]\nversion = \"{version} (82e1608df 2023-12-21)\"\n\n[pkg.rust.target.{HOST}]\navailable = true\n"
+                ),
+            );
+        }
+        write(
+            &dir.path().join(".rustup/settings.toml"),
+            &format!(
+                "version = \"12\"\ndefault_host_triple = \"{HOST}\"\ndefault_toolchain = \"stable-{HOST}\"\n\n[overrides]\n"
+            ),
+        );
+        write(&dir.path().join(".cargo/bin/rustup"), "rustup");
+        write(&dir.path().join(".cargo/bin/cargo"), "rustup");
+        write(&dir.path().join(".cargo/bin/rg"), "ripgrep");
+        std::fs::create_dir_all(dir.path().join("project/src")).unwrap();
+        dir
+    }
+
+    fn detector(home: &Path, toolchain: Option<&str>, cwd: &Path) -> RustupDetector {
+        RustupDetector {
+            rustup_home: Some(home.join(".rustup")),
+            cargo_home: Some(home.join(".cargo")),
+            toolchain_override: toolchain.map(str::to_string),
+            cwd: Some(cwd.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn test_default_toolchain() {
+        let home = fixture();
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let detector = detector(home.path(), None, home.path());
+        let ctx = make_context("cargo", vec![&cargo]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "rustup");
+        assert_eq!(result.package_name, Some(format!("stable-{HOST}")));
+        assert_eq!(result.version, Some("1.75.0".to_string()));
+        assert_eq!(
+            result.resolved_path,
+            home.path()
+                .join(format!(".rustup/toolchains/stable-{HOST}/bin/cargo"))
+        );
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.extra["selected_by"], "default toolchain");
+        assert_eq!(info.provides, vec!["cargo", "rustc"]);
+    }
+
+    #[test]
+    fn test_environment_override() {
+        let home = fixture();
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let result = detector(home.path(), Some("nightly"), home.path())
+            .detect(&make_context("cargo", vec![&cargo]))
+            .unwrap();
+        assert_eq!(result.package_name, Some(format!("nightly-{HOST}")));
+        assert_eq!(result.version, Some("1.77.0-nightly".to_string()));
+    }
+
+    #[test]
+    fn test_toolchain_file_walks_up() {
+        let home = fixture();
+        write(
+            &home.path().join("project/rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"nightly\"\ncomponents = [\"rustfmt\"]\n",
+        );
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let detector = detector(home.path(), None, &home.path().join("project/src"));
+        let ctx = make_context("cargo", vec![&cargo]);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some(format!("nightly-{HOST}")));
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert!(info.extra["selected_by"].ends_with("rust-toolchain.toml"));
+    }
+
+    #[test]
+    fn test_directory_override() {
+        let home = fixture();
+        let project = home.path().join("project");
+        write(
+            &home.path().join(".rustup/settings.toml"),
+            &format!(
+                "default_toolchain = \"stable-{HOST}\"\n\n[overrides]\n\"{}\" = \"nightly-{HOST}\"\n",
+                project.display()
+            ),
+        );
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let result = detector(home.path(), None, &project.join("src"))
+            .detect(&make_context("cargo", vec![&cargo]))
+            .unwrap();
+        assert_eq!(result.package_name, Some(format!("nightly-{HOST}")));
+    }
+
+    #[test]
+    fn test_legacy_toolchain_file() {
+        let home = fixture();
+        write(&home.path().join("project/rust-toolchain"), "nightly\n");
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let result = detector(home.path(), None, &home.path().join("project"))
+            .detect(&make_context("cargo", vec![&cargo]))
+            .unwrap();
+        assert_eq!(result.package_name, Some(format!("nightly-{HOST}")));
+    }
+
+    #[test]
+    fn test_missing_toolchain_is_medium() {
+        let home = fixture();
+        let cargo = home.path().join(".cargo/bin/cargo");
+        let detector = detector(home.path(), Some("1.60.0"), home.path());
+        let ctx = make_context("cargo", vec![&cargo]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("1.60.0".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Unverified);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_and_hard_linked_proxies() {
+        let home = fixture();
+        let bin = home.path().join(".cargo/bin");
+        std::os::unix::fs::symlink(bin.join("rustup"), bin.join("cargo-nextest-proxy")).unwrap();
+        std::fs::hard_link(bin.join("rustup"), bin.join("rustc-wrapper")).unwrap();
+        let detector = detector(home.path(), None, home.path());
+
+        let symlinked = bin.join("cargo-nextest-proxy");
+        let ctx = make_context("cargo-nextest-proxy", vec![&symlinked, &bin.join("rustup")]);
+        assert!(detector.is_proxy(&bin, &ctx, &symlinked));
+
+        let hard_linked = bin.join("rustc-wrapper");
+        let ctx = make_context("rustc-wrapper", vec![&hard_linked]);
+        assert!(detector.is_proxy(&bin, &ctx, &hard_linked));
+
+        let rg = bin.join("rg");
+        let ctx = make_context("rg", vec![&rg]);
+        assert!(!detector.is_proxy(&bin, &ctx, &rg));
+        assert!(detector.detect(&ctx).is_none());
+    }
+
+    #[test]
+    fn test_direct_toolchain_path() {
+        let home = fixture();
+        let rustc = home
+            .path()
+            .join(format!(".rustup/toolchains/nightly-{HOST}/bin/rustc"));
+        let detector = detector(home.path(), None, home.path());
+        let ctx = make_context("rustc", vec![&rustc]);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some(format!("nightly-{HOST}")));
+        assert_eq!(result.version, Some("1.77.0-nightly".to_string()));
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+    }
+
+    #[test]
+    fn test_read_toolchain_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("rust-toolchain.toml");
+        std::fs::write(&file, "[toolchain]\nchannel = \"1.75\"\n").unwrap();
+        assert_eq!(read_toolchain_file(&file), Some("1.75".to_string()));
+        std::fs::write(&file, "1.74.1\n").unwrap();
+        assert_eq!(read_toolchain_file(&file), Some("1.74.1".to_string()));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for Scoop packages (Windows).
@@ -42,7 +42,10 @@ impl PackageManagerDetector for ScoopDetector {
                     manager_name: self.name().to_string(),
                     package_name: Some(ctx.command_name.clone()),
                     version: None,
+                    details: Default::default(),
                     confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
@@ -67,6 +70,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -1,5 +1,12 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::info::modified_time;
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
 use crate::platform::Platform;
+use std::path::Path;
+
+const SNAP_ROOT: &str = "/snap";
 
 /// Detector for Snap packages.
 pub struct SnapDetector;
@@ -47,7 +54,10 @@ impl PackageManagerDetector for SnapDetector {
                     manager_name: self.name().to_string(),
                     package_name,
                     version: None,
+                    details: Default::default(),
                     confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
@@ -56,6 +66,83 @@ impl PackageManagerDetector for SnapDetector {
 
         None
     }
+
+    fn verify(&self, _ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        match result.package_name.clone() {
+            Some(name) => verify_snap_yaml(Path::new(SNAP_ROOT), &name, result),
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, _ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        read_snap_info(Path::new(SNAP_ROOT), result.package_name.as_deref()?)
+    }
+}
+
+/// Read summary, license and the apps list from the snap's `meta/snap.yaml`.
+fn read_snap_info(snap_root: &Path, name: &str) -> Option<PackageInfo> {
+    let current = snap_root.join(name).join("current");
+    let content = std::fs::read_to_string(current.join("meta/snap.yaml")).ok()?;
+    let mut info = PackageInfo {
+        install_date: modified_time(&current),
+        installed_on_request: Some(true),
+        ..Default::default()
+    };
+
+    let mut in_apps = false;
+    for line in content.lines() {
+        if !line.starts_with(' ') {
+            in_apps = line.trim_end() == "apps:";
+        }
+        if in_apps {
+            // App names are the keys indented one level under `apps:`
+            if let Some(app) = line.strip_prefix("  ").and_then(|l| l.strip_suffix(':')) {
+                if !app.starts_with(' ') {
+                    // Snap apps are exposed as {snap}.{app}, or {snap} for the app named after it
+                    info.provides.push(if app == name {
+                        name.to_string()
+                    } else {
+                        format!("{name}.{app}")
+                    });
+                }
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+            match key {
+                "summary" => info.description = Some(value.to_string()),
+                "license" => info.license = Some(value.to_string()),
+                "website" => info.homepage = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+    info.provides.sort();
+    Some(info)
+}
+
+/// Check the mounted snap's `meta/snap.yaml` for the package name and version.
+fn verify_snap_yaml(snap_root: &Path, name: &str, result: &mut DetectionResult) -> Verification {
+    let yaml_path = snap_root.join(name).join("current/meta/snap.yaml");
+    let Ok(content) = std::fs::read_to_string(yaml_path) else {
+        return Verification::Unverified;
+    };
+
+    let field = |key: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .map(|v| v.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        })
+    };
+
+    if field("name:").as_deref() != Some(name) {
+        return Verification::Contradicted;
+    }
+    if let Some(version) = field("version:") {
+        result.version = Some(version);
+    }
+    Verification::Verified
 }
 
 fn extract_snap_package_name(path: &str) -> Option<String> {
@@ -85,6 +172,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -139,4 +227,59 @@ mod tests {
         assert!(!detector.supports_platform(Platform::MacOS));
         assert!(!detector.supports_platform(Platform::Windows));
     }
+
+    #[test]
+    fn test_verify_reads_snap_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let meta = dir.path().join("code/current/meta");
+        std::fs::create_dir_all(&meta).unwrap();
+        std::fs::write(
+            meta.join("snap.yaml"),
+            "name: code\nversion: '1.85.1'\nsummary: Code editing.\n",
+        )
+        .unwrap();
+
+        let detector = SnapDetector::new();
+        let ctx = make_context("code", vec!["/snap/bin/code"], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(
+            verify_snap_yaml(dir.path(), "code", &mut result),
+            Verification::Verified
+        );
+        assert_eq!(result.version, Some("1.85.1".to_string()));
+    }
+
+    #[test]
+    fn test_read_snap_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let meta = dir.path().join("lxd/current/meta");
+        std::fs::create_dir_all(&meta).unwrap();
+        std::fs::write(
+            meta.join("snap.yaml"),
+            "name: lxd\nversion: '5.19'\nsummary: LXD - container and VM manager\n\
+             license: AGPL-3.0\napps:\n  lxc:\n    command: bin/lxc\n  lxd:\n    \
+             command: bin/lxd\n    daemon: simple\n",
+        )
+        .unwrap();
+
+        let info = read_snap_info(dir.path(), "lxd").unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("LXD - container and VM manager")
+        );
+        assert_eq!(info.license.as_deref(), Some("AGPL-3.0"));
+        assert_eq!(info.provides, vec!["lxd", "lxd.lxc"]);
+    }
+
+    #[test]
+    fn test_verify_missing_snap_yaml_is_unverified() {
+        let dir = tempfile::tempdir().unwrap();
+        let detector = SnapDetector::new();
+        let ctx = make_context("code", vec!["/snap/bin/code"], Platform::Linux);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(
+            verify_snap_yaml(dir.path(), "code", &mut result),
+            Verification::Unverified
+        );
+    }
 }
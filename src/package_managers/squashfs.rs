@@ -0,0 +1,444 @@
+//! Minimal SquashFS 4.0 reader for the filesystem appended to type 2
+//! AppImages: just enough to read a small file from the root directory.
+//! Only gzip compression is supported; other images read as `None`.
+
+use super::binary::{read_at, Endian};
+use flate2::read::ZlibDecoder;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const MAGIC: &[u8] = b"hsqs";
+const SUPERBLOCK_LEN: usize = 96;
+const COMPRESSION_GZIP: u16 = 1;
+
+/// Uncompressed size of a metadata block (inodes, directories, fragment table).
+const METADATA_BLOCK_SIZE: u64 = 8192;
+/// Set in a metadata block header when the block is stored uncompressed.
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+/// Set in a data block size when the block is stored uncompressed.
+const DATA_UNCOMPRESSED: u32 = 1 << 24;
+/// Fragment index of files whose tail is not in a fragment block.
+const NO_FRAGMENT: u32 = u32::MAX;
+/// Fragment table entries per metadata block.
+const FRAGMENTS_PER_BLOCK: u32 = 512;
+
+const BASIC_DIRECTORY: u16 = 1;
+const BASIC_FILE: u16 = 2;
+const EXTENDED_DIRECTORY: u16 = 8;
+const EXTENDED_FILE: u16 = 9;
+
+/// Largest file or directory listing we are willing to read.
+const MAX_READ_SIZE: u64 = 1024 * 1024;
+
+const LE: Endian = Endian { little: true };
+
+/// Read the first regular file in the root directory whose name satisfies
+/// `matches`, from the SquashFS image starting `offset` bytes into `path`.
+pub(super) fn read_root_file(
+    path: &Path,
+    offset: u64,
+    matches: impl Fn(&str) -> bool,
+) -> Option<Vec<u8>> {
+    let mut image = Image::open(path, offset)?;
+    let entries = image.root_entries()?;
+    let inode = entries
+        .into_iter()
+        .find(|entry| entry.kind == BASIC_FILE && matches(&entry.name))?
+        .inode;
+    image.read_file(inode)
+}
+
+/// An opened image, with the table locations from its superblock.
+/// Table offsets are relative to the start of the image.
+struct Image {
+    file: File,
+    start: u64,
+    block_size: u32,
+    root_inode: u64,
+    inode_table: u64,
+    directory_table: u64,
+    fragment_table: u64,
+}
+
+/// A position in a metadata table, loading blocks as they are read.
+struct Metadata {
+    /// Absolute file offset of the next block to load
+    next_block: u64,
+    data: Vec<u8>,
+    at: usize,
+}
+
+struct DirEntry {
+    name: String,
+    kind: u16,
+    /// Inode reference: metadata block (relative to the inode table) << 16 | offset
+    inode: u64,
+}
+
+impl Image {
+    fn open(path: &Path, start: u64) -> Option<Self> {
+        let mut file = File::open(path).ok()?;
+        let sb = read_at(&mut file, start, SUPERBLOCK_LEN)?;
+        if !sb.starts_with(MAGIC) || LE.u16(&sb, 20)? != COMPRESSION_GZIP || LE.u16(&sb, 28)? != 4 {
+            return None;
+        }
+        Some(Self {
+            file,
+            start,
+            block_size: LE.u32(&sb, 12)?,
+            root_inode: LE.u64(&sb, 32)?,
+            inode_table: LE.u64(&sb, 64)?,
+            directory_table: LE.u64(&sb, 72)?,
+            fragment_table: LE.u64(&sb, 80)?,
+        })
+    }
+
+    /// Start reading the metadata table at `table`, `offset` bytes into the
+    /// (uncompressed) block `block` bytes after the table start.
+    fn metadata(&self, table: u64, block: u64, offset: usize) -> Option<Metadata> {
+        Some(Metadata {
+            next_block: self.start.checked_add(table)?.checked_add(block)?,
+            data: Vec::new(),
+            at: offset,
+        })
+    }
+
+    /// Read the next `len` bytes from a metadata table.
+    fn read(&mut self, metadata: &mut Metadata, len: usize) -> Option<Vec<u8>> {
+        let end = metadata.at.checked_add(len)?;
+        while metadata.data.len() < end {
+            let header = read_at(&mut self.file, metadata.next_block, 2)?;
+            let header = LE.u16(&header, 0)?;
+            let size = header & !METADATA_UNCOMPRESSED;
+            let raw = read_at(
+                &mut self.file,
+                metadata.next_block.checked_add(2)?,
+                size as usize,
+            )?;
+            let block = decompress(
+                raw,
+                header & METADATA_UNCOMPRESSED == 0,
+                METADATA_BLOCK_SIZE,
+            )?;
+            metadata.data.extend(block);
+            metadata.next_block = metadata.next_block.checked_add(2 + u64::from(size))?;
+        }
+        let bytes = metadata.data[metadata.at..end].to_vec();
+        metadata.at = end;
+        Some(bytes)
+    }
+
+    /// Start reading the inode an inode reference points at.
+    fn inode(&self, reference: u64) -> Option<Metadata> {
+        self.metadata(
+            self.inode_table,
+            reference >> 16,
+            (reference & 0xFFFF) as usize,
+        )
+    }
+
+    /// The entries of the root directory.
+    fn root_entries(&mut self) -> Option<Vec<DirEntry>> {
+        let mut inode = self.inode(self.root_inode)?;
+        let header = self.read(&mut inode, 16)?;
+        let (block, size, offset) = match LE.u16(&header, 0)? {
+            BASIC_DIRECTORY => {
+                let dir = self.read(&mut inode, 16)?;
+                (
+                    LE.u32(&dir, 0)?,
+                    u32::from(LE.u16(&dir, 8)?),
+                    LE.u16(&dir, 10)?,
+                )
+            }
+            EXTENDED_DIRECTORY => {
+                let dir = self.read(&mut inode, 24)?;
+                (LE.u32(&dir, 8)?, LE.u32(&dir, 4)?, LE.u16(&dir, 18)?)
+            }
+            _ => return None,
+        };
+        // The stored size counts three bytes for the `.` and `..` entries
+        let size = u64::from(size.saturating_sub(3));
+        if size > MAX_READ_SIZE {
+            return None;
+        }
+
+        let mut listing = self.metadata(self.directory_table, block.into(), offset.into())?;
+        let listing = self.read(&mut listing, size as usize)?;
+        parse_directory(&listing)
+    }
+
+    /// Read a regular file's contents: its full blocks, then its tail from a
+    /// fragment block.
+    fn read_file(&mut self, reference: u64) -> Option<Vec<u8>> {
+        let mut inode = self.inode(reference)?;
+        let header = self.read(&mut inode, 16)?;
+        let (blocks_start, fragment, fragment_offset, size) = match LE.u16(&header, 0)? {
+            BASIC_FILE => {
+                let file = self.read(&mut inode, 16)?;
+                (
+                    u64::from(LE.u32(&file, 0)?),
+                    LE.u32(&file, 4)?,
+                    LE.u32(&file, 8)?,
+                    u64::from(LE.u32(&file, 12)?),
+                )
+            }
+            EXTENDED_FILE => {
+                let file = self.read(&mut inode, 40)?;
+                (
+                    LE.u64(&file, 0)?,
+                    LE.u32(&file, 28)?,
+                    LE.u32(&file, 32)?,
+                    LE.u64(&file, 8)?,
+                )
+            }
+            _ => return None,
+        };
+        let block_size = u64::from(self.block_size);
+        // SquashFS blocks are at most 1 MiB
+        if size > MAX_READ_SIZE || block_size == 0 || block_size > MAX_READ_SIZE {
+            return None;
+        }
+
+        let full_blocks = if fragment == NO_FRAGMENT {
+            size.div_ceil(block_size)
+        } else {
+            size / block_size
+        };
+        let sizes = self.read(&mut inode, full_blocks as usize * 4)?;
+        let mut contents = Vec::new();
+        let mut position = self.start.checked_add(blocks_start)?;
+        for sizes in sizes.chunks_exact(4) {
+            let stored = LE.u32(sizes, 0)?;
+            let on_disk = stored & !DATA_UNCOMPRESSED;
+            if on_disk == 0 {
+                // Sparse block
+                contents.resize(contents.len() + block_size as usize, 0);
+                continue;
+            }
+            let raw = read_at(&mut self.file, position, on_disk as usize)?;
+            contents.extend(decompress(
+                raw,
+                stored & DATA_UNCOMPRESSED == 0,
+                block_size,
+            )?);
+            position += u64::from(on_disk);
+        }
+
+        if fragment != NO_FRAGMENT {
+            let tail = self.fragment(fragment)?;
+            let start = fragment_offset as usize;
+            let len = (size % block_size) as usize;
+            contents.extend_from_slice(tail.get(start..start.checked_add(len)?)?);
+        }
+        contents.truncate(size as usize);
+        Some(contents)
+    }
+
+    /// The contents of fragment block `index`.
+    fn fragment(&mut self, index: u32) -> Option<Vec<u8>> {
+        // The table is a list of pointers to metadata blocks of 16-byte entries
+        let pointer_at = self
+            .start
+            .checked_add(self.fragment_table)?
+            .checked_add(u64::from(index / FRAGMENTS_PER_BLOCK) * 8)?;
+        let pointer = LE.u64(&read_at(&mut self.file, pointer_at, 8)?, 0)?;
+        let mut entry = self.metadata(0, pointer, (index % FRAGMENTS_PER_BLOCK) as usize * 16)?;
+        let entry = self.read(&mut entry, 16)?;
+        let (start, stored) = (LE.u64(&entry, 0)?, LE.u32(&entry, 8)?);
+
+        let on_disk = stored & !DATA_UNCOMPRESSED;
+        let raw = read_at(
+            &mut self.file,
+            self.start.checked_add(start)?,
+            on_disk as usize,
+        )?;
+        decompress(raw, stored & DATA_UNCOMPRESSED == 0, self.block_size.into())
+    }
+}
+
+/// Parse a directory listing: runs of entries, each run preceded by a
+/// header giving the metadata block their inodes are in.
+fn parse_directory(listing: &[u8]) -> Option<Vec<DirEntry>> {
+    let mut entries = Vec::new();
+    let mut at = 0;
+    while at < listing.len() {
+        let count = LE.u32(listing, at)?.checked_add(1)?;
+        let block = u64::from(LE.u32(listing, at + 4)?);
+        at += 12;
+        for _ in 0..count {
+            let offset = u64::from(LE.u16(listing, at)?);
+            let kind = LE.u16(listing, at + 4)?;
+            let name_len = usize::from(LE.u16(listing, at + 6)?) + 1;
+            let name = listing.get(at + 8..at + 8 + name_len)?;
+            entries.push(DirEntry {
+                name: String::from_utf8_lossy(name).to_string(),
+                kind,
+                inode: block << 16 | offset,
+            });
+            at += 8 + name_len;
+        }
+    }
+    Some(entries)
+}
+
+fn decompress(data: Vec<u8>, compressed: bool, limit: u64) -> Option<Vec<u8>> {
+    if !compressed {
+        return Some(data);
+    }
+    let mut out = Vec::new();
+    ZlibDecoder::new(data.as_slice())
+        .take(limit)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const BLOCK_SIZE: u32 = 4096;
+
+    /// A gzip-compressed metadata block.
+    fn metadata_block(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut block = (compressed.len() as u16).to_le_bytes().to_vec();
+        block.extend(compressed);
+        block
+    }
+
+    fn inode_header(kind: u16, number: u32) -> Vec<u8> {
+        let mut header = kind.to_le_bytes().to_vec();
+        header.extend(0o644u16.to_le_bytes());
+        header.extend([0u8; 8]); // uid, gid, mtime
+        header.extend(number.to_le_bytes());
+        header
+    }
+
+    /// Build an image with `files` in its root directory, laid out like
+    /// `mksquashfs` does: full data blocks stored uncompressed, file tails
+    /// packed into one fragment block, gzip-compressed metadata.
+    pub(in crate::package_managers) fn build_squashfs(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut image = vec![0u8; SUPERBLOCK_LEN];
+
+        let mut fragment = Vec::new();
+        let mut inodes = Vec::new();
+        let mut entries = Vec::new();
+        for (i, (name, contents)) in files.iter().enumerate() {
+            let blocks_start = image.len() as u32;
+            let full = contents.len() / BLOCK_SIZE as usize * BLOCK_SIZE as usize;
+            let mut sizes = Vec::new();
+            for block in contents[..full].chunks(BLOCK_SIZE as usize) {
+                image.extend_from_slice(block);
+                sizes.extend((block.len() as u32 | DATA_UNCOMPRESSED).to_le_bytes());
+            }
+            let fragment_offset = fragment.len() as u32;
+            fragment.extend_from_slice(&contents[full..]);
+
+            entries.push((name.to_string(), inodes.len() as u16, i as u32 + 1));
+            inodes.extend(inode_header(BASIC_FILE, i as u32 + 1));
+            inodes.extend(blocks_start.to_le_bytes());
+            inodes.extend(0u32.to_le_bytes()); // fragment index
+            inodes.extend(fragment_offset.to_le_bytes());
+            inodes.extend((contents.len() as u32).to_le_bytes());
+            inodes.extend(sizes);
+        }
+        let fragment_start = image.len() as u64;
+        image.extend_from_slice(&fragment);
+
+        let mut listing = (files.len() as u32 - 1).to_le_bytes().to_vec();
+        listing.extend(0u32.to_le_bytes()); // inode metadata block
+        listing.extend(1u32.to_le_bytes()); // first inode number
+        for (name, offset, number) in &entries {
+            listing.extend(offset.to_le_bytes());
+            listing.extend(((number - 1) as i16).to_le_bytes());
+            listing.extend(BASIC_FILE.to_le_bytes());
+            listing.extend((name.len() as u16 - 1).to_le_bytes());
+            listing.extend(name.as_bytes());
+        }
+
+        let root_offset = inodes.len() as u64;
+        let root_number = files.len() as u32 + 1;
+        inodes.extend(inode_header(BASIC_DIRECTORY, root_number));
+        inodes.extend(0u32.to_le_bytes()); // directory block
+        inodes.extend(2u32.to_le_bytes()); // link count
+        inodes.extend((listing.len() as u16 + 3).to_le_bytes());
+        inodes.extend(0u16.to_le_bytes()); // offset in directory block
+        inodes.extend((root_number + 1).to_le_bytes()); // parent
+
+        let inode_table = image.len() as u64;
+        image.extend(metadata_block(&inodes));
+        let directory_table = image.len() as u64;
+        image.extend(metadata_block(&listing));
+
+        let fragment_entries = image.len() as u64;
+        let mut entry = fragment_start.to_le_bytes().to_vec();
+        entry.extend((fragment.len() as u32 | DATA_UNCOMPRESSED).to_le_bytes());
+        entry.extend(0u32.to_le_bytes());
+        image.extend(metadata_block(&entry));
+        let fragment_table = image.len() as u64;
+        image.extend(fragment_entries.to_le_bytes());
+
+        let bytes_used = image.len() as u64;
+        let sb = &mut image[..SUPERBLOCK_LEN];
+        sb[..4].copy_from_slice(MAGIC);
+        sb[4..8].copy_from_slice(&root_number.to_le_bytes());
+        sb[12..16].copy_from_slice(&BLOCK_SIZE.to_le_bytes());
+        sb[16..20].copy_from_slice(&1u32.to_le_bytes());
+        sb[20..22].copy_from_slice(&COMPRESSION_GZIP.to_le_bytes());
+        sb[22..24].copy_from_slice(&12u16.to_le_bytes());
+        sb[28..30].copy_from_slice(&4u16.to_le_bytes());
+        sb[32..40].copy_from_slice(&root_offset.to_le_bytes());
+        sb[40..48].copy_from_slice(&bytes_used.to_le_bytes());
+        sb[64..72].copy_from_slice(&inode_table.to_le_bytes());
+        sb[72..80].copy_from_slice(&directory_table.to_le_bytes());
+        sb[80..88].copy_from_slice(&fragment_table.to_le_bytes());
+        image
+    }
+
+    #[test]
+    fn test_reads_root_files() {
+        let large: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        let mut data = b"runtime".to_vec();
+        data.extend(build_squashfs(&[
+            ("AppRun", b"#!/bin/sh\n"),
+            ("app.desktop", b"[Desktop Entry]\nName=App\n"),
+            ("large.bin", &large),
+        ]));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image");
+        std::fs::write(&path, data).unwrap();
+
+        assert_eq!(
+            read_root_file(&path, 7, |name| name.ends_with(".desktop")).as_deref(),
+            Some(&b"[Desktop Entry]\nName=App\n"[..])
+        );
+        assert_eq!(
+            read_root_file(&path, 7, |name| name == "large.bin"),
+            Some(large)
+        );
+        assert!(read_root_file(&path, 7, |name| name == "missing").is_none());
+        // Not at the start of an image
+        assert!(read_root_file(&path, 0, |_| true).is_none());
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_fragment_table() {
+        let mut data = b"runtime".to_vec();
+        let mut image = build_squashfs(&[("app.desktop", b"[Desktop Entry]\n")]);
+        // A fragment table at the very end of the offset range
+        image[80..88].copy_from_slice(&(u64::MAX - 7).to_le_bytes());
+        data.extend(image);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image");
+        std::fs::write(&path, data).unwrap();
+
+        assert!(read_root_file(&path, 7, |name| name.ends_with(".desktop")).is_none());
+    }
+}
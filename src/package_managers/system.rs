@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for system/OS standard binaries.
@@ -55,7 +55,10 @@ impl PackageManagerDetector for SystemDetector {
                 manager_name: self.name().to_string(),
                 package_name: None,
                 version: None,
+                details: Default::default(),
                 confidence: Confidence::Medium,
+                verification: Verification::Unverified,
+                info: None,
                 command_path: ctx.command_path.clone(),
                 resolved_path: ctx.resolved_path.clone(),
             });
@@ -78,6 +81,7 @@ mod tests {
             symlink_chain: vec![path.clone()],
             resolved_path: path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
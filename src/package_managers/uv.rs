@@ -0,0 +1,410 @@
+use super::info::modified_time;
+use super::python::{
+    find_dist_info, parse_dist_info_name, read_core_metadata, site_packages_dirs,
+    venv_python_version,
+};
+use super::{
+    Confidence, DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector,
+    Verification,
+};
+use crate::platform::Platform;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Detector for `uv tool install` packages.
+/// uv installs each tool into its own environment under `<tool dir>/<name>`
+/// and links (or on Windows, copies) its executables into the tool bin dir.
+pub struct UvDetector {
+    /// `UV_TOOL_DIR`, or `<uv data dir>/tools`
+    tool_dir: Option<PathBuf>,
+    /// `UV_TOOL_BIN_DIR`, or `~/.local/bin` by the XDG rules
+    bin_dir: Option<PathBuf>,
+}
+
+impl UvDetector {
+    pub fn new() -> Self {
+        let env_dir = |key: &str| {
+            std::env::var_os(key)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+        let home = dirs::home_dir();
+        let data_home =
+            env_dir("XDG_DATA_HOME").or_else(|| home.as_ref().map(|h| h.join(".local/share")));
+
+        // `%APPDATA%\uv\data` on Windows; `$XDG_DATA_HOME/uv` or `~/.local/share/uv` elsewhere
+        let data_dir = if cfg!(windows) {
+            dirs::data_dir().map(|d| d.join("uv/data"))
+        } else {
+            data_home.as_ref().map(|d| d.join("uv"))
+        };
+        let bin_dir = env_dir("UV_TOOL_BIN_DIR")
+            .or_else(|| env_dir("XDG_BIN_HOME"))
+            .or_else(|| env_dir("XDG_DATA_HOME")?.parent().map(|d| d.join("bin")))
+            .or_else(|| home.map(|h| h.join(".local/bin")));
+
+        Self {
+            tool_dir: env_dir("UV_TOOL_DIR").or_else(|| data_dir.map(|d| d.join("tools"))),
+            bin_dir,
+        }
+    }
+
+    /// The tool environment a path belongs to: directly for a chain element
+    /// inside the tool dir, or through a receipt entry point for copies in
+    /// the bin dir.
+    fn find_tool(&self, ctx: &DetectionContext) -> Option<PathBuf> {
+        let tool_dir = self.tool_dir.as_ref()?;
+        let in_tool_dir = ctx.candidate_paths().find_map(|path| {
+            let name = path.strip_prefix(tool_dir).ok()?.iter().next()?;
+            Some(tool_dir.join(name))
+        });
+        if in_tool_dir.is_some() {
+            return in_tool_dir;
+        }
+
+        let bin_dir = self.bin_dir.as_ref()?;
+        if ctx.command_path.parent() != Some(bin_dir.as_path()) {
+            return None;
+        }
+        std::fs::read_dir(tool_dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|tool| {
+                read_receipt(tool).is_some_and(|receipt| {
+                    receipt
+                        .entrypoints
+                        .iter()
+                        .any(|e| e.install_path.as_deref() == Some(ctx.command_path.as_path()))
+                })
+            })
+    }
+}
+
+/// `uv-receipt.toml`, written into every tool environment.
+#[derive(Debug, Deserialize)]
+struct ReceiptFile {
+    tool: Receipt,
+}
+
+#[derive(Debug, Deserialize)]
+struct Receipt {
+    /// The requested package first, then the `--with` packages
+    #[serde(default)]
+    requirements: Vec<Requirement>,
+    #[serde(default)]
+    entrypoints: Vec<Entrypoint>,
+}
+
+/// A requirement as a table (current uv) or a PEP 508 string (uv before 0.3).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Requirement {
+    Table {
+        name: String,
+        #[serde(default)]
+        extras: Vec<String>,
+        specifier: Option<String>,
+        git: Option<String>,
+        url: Option<String>,
+        path: Option<String>,
+        directory: Option<String>,
+    },
+    Pep508(String),
+}
+
+impl Requirement {
+    fn name(&self) -> &str {
+        match self {
+            Requirement::Table { name, .. } => name,
+            Requirement::Pep508(spec) => spec
+                .split(|c: char| "<>=!~;[@ ".contains(c))
+                .next()
+                .unwrap_or(spec),
+        }
+    }
+
+    /// The version constraint or source, e.g. `>=24.1` or `@ git+https://...`.
+    fn constraint(&self) -> Option<String> {
+        match self {
+            Requirement::Table {
+                specifier,
+                git,
+                url,
+                path,
+                directory,
+                ..
+            } => {
+                let source = git
+                    .as_ref()
+                    .map(|git| format!("git+{git}"))
+                    .or_else(|| url.clone().or_else(|| path.clone()).or(directory.clone()));
+                source
+                    .map(|source| format!("@ {source}"))
+                    .or_else(|| specifier.clone().filter(|s| !s.is_empty()))
+            }
+            Requirement::Pep508(spec) => {
+                let rest = spec[self.name().len()..].trim();
+                // Extras are shown with the name
+                let rest = match rest.strip_prefix('[') {
+                    Some(after) => after.split_once(']').map_or("", |(_, r)| r).trim(),
+                    None => rest,
+                };
+                Some(rest.to_string()).filter(|r| !r.is_empty())
+            }
+        }
+    }
+
+    /// The requirement as the user would have typed it.
+    fn display(&self) -> String {
+        let mut display = self.name().to_string();
+        if let Requirement::Table { extras, .. } = self {
+            if !extras.is_empty() {
+                display.push_str(&format!("[{}]", extras.join(",")));
+            }
+        }
+        if let Some(constraint) = self.constraint() {
+            if constraint.starts_with('@') {
+                display.push(' ');
+            }
+            display.push_str(&constraint);
+        }
+        display
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Entrypoint {
+    name: String,
+    #[serde(rename = "install-path")]
+    install_path: Option<PathBuf>,
+}
+
+fn read_receipt(tool: &Path) -> Option<Receipt> {
+    let content = std::fs::read_to_string(tool.join("uv-receipt.toml")).ok()?;
+    toml::from_str::<ReceiptFile>(&content)
+        .ok()
+        .map(|file| file.tool)
+}
+
+/// Installed version of `package` in the tool environment.
+fn installed_version(tool: &Path, package: &str) -> Option<String> {
+    let dist_info = site_packages_dirs(tool)
+        .iter()
+        .find_map(|site| find_dist_info(site, package))?;
+    let dir_name = dist_info.file_name()?.to_string_lossy().to_string();
+    parse_dist_info_name(&dir_name).map(|(_, version)| version.to_string())
+}
+
+impl PackageManagerDetector for UvDetector {
+    fn id(&self) -> &'static str {
+        "uv"
+    }
+
+    fn name(&self) -> &str {
+        "uv tool"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true // uv is cross-platform
+    }
+
+    fn priority(&self) -> i32 {
+        85 // Same as pipx, ahead of pip (80) for the scripts in the tool environment
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let tool = self.find_tool(ctx)?;
+        // The environment is named after the package; the receipt confirms it
+        // during verification
+        let package_name = tool.file_name()?.to_string_lossy().to_string();
+
+        Some(DetectionResult {
+            manager_id: self.id().to_string(),
+            manager_name: self.name().to_string(),
+            version: installed_version(&tool, &package_name),
+            package_name: Some(package_name),
+            details: Default::default(),
+            confidence: Confidence::Medium,
+            verification: Verification::Unverified,
+            info: None,
+            command_path: ctx.command_path.clone(),
+            resolved_path: ctx.resolved_path.clone(),
+        })
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        let Some(tool) = self.find_tool(ctx) else {
+            return Verification::Unverified;
+        };
+        let Some(receipt) = read_receipt(&tool) else {
+            return Verification::Unverified;
+        };
+        let Some(requested) = receipt.requirements.first() else {
+            return Verification::Unverified;
+        };
+        result.version = installed_version(&tool, requested.name());
+        result.package_name = Some(requested.name().to_string());
+        Verification::Verified
+    }
+
+    fn info(&self, ctx: &DetectionContext, _result: &DetectionResult) -> Option<PackageInfo> {
+        let tool = self.find_tool(ctx)?;
+        let receipt = read_receipt(&tool)?;
+        let requested = receipt.requirements.first()?;
+
+        let mut info = site_packages_dirs(&tool)
+            .iter()
+            .find_map(|site| find_dist_info(site, requested.name()))
+            .map(|dist_info| read_core_metadata(&dist_info))
+            .unwrap_or_default();
+        info.install_date = modified_time(&tool.join("uv-receipt.toml"));
+        info.installed_on_request = Some(true);
+        info.provides = receipt.entrypoints.iter().map(|e| e.name.clone()).collect();
+        info.provides.sort();
+
+        let with: Vec<String> = receipt.requirements[1..]
+            .iter()
+            .map(Requirement::display)
+            .collect();
+        let details = [
+            ("constraint", requested.constraint()),
+            ("with", Some(with.join(", ")).filter(|w| !w.is_empty())),
+            ("python_version", venv_python_version(&tool)),
+        ];
+        for (key, value) in details {
+            if let Some(value) = value {
+                info.extra.insert(key.to_string(), value);
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, paths: Vec<&Path>) -> DetectionContext {
+        let chain: Vec<PathBuf> = paths.iter().map(|p| p.to_path_buf()).collect();
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: chain[0].clone(),
+            symlink_chain: chain.clone(),
+            resolved_path: chain.last().unwrap().clone(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    /// A tool dir with `black` installed `--with black[jupyter]`-style extras.
+    fn fixture(bin_dir: &Path) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tools/black");
+        let dist = tool.join("lib/python3.12/site-packages/black-24.1.1.dist-info");
+        std::fs::create_dir_all(&dist).unwrap();
+        std::fs::create_dir_all(tool.join("bin")).unwrap();
+        std::fs::write(tool.join("bin/black"), "#!python\n").unwrap();
+        std::fs::write(
+            dist.join("METADATA"),
+            "Name: black\nSummary: The uncompromising code formatter.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tool.join("pyvenv.cfg"),
+            "home = /usr/bin\nimplementation = CPython\nuv = 0.4.0\nversion_info = 3.12.4\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tool.join("uv-receipt.toml"),
+            format!(
+                r#"[tool]
+requirements = [
+    {{ name = "black", specifier = ">=24" }},
+    {{ name = "tokenize-rt", extras = ["cli"] }},
+    {{ name = "ipython", git = "https://github.com/ipython/ipython" }},
+]
+entrypoints = [
+    {{ name = "blackd", install-path = "{bin}/blackd" }},
+    {{ name = "black", install-path = "{bin}/black" }},
+]
+"#,
+                bin = bin_dir.display()
+            ),
+        )
+        .unwrap();
+        dir
+    }
+
+    fn detector(dir: &Path, bin_dir: &Path) -> UvDetector {
+        UvDetector {
+            tool_dir: Some(dir.join("tools")),
+            bin_dir: Some(bin_dir.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn test_detects_tool_through_symlink() {
+        let bin_dir = Path::new("/home/user/.local/bin");
+        let dir = fixture(bin_dir);
+        let detector = detector(dir.path(), bin_dir);
+        let target = dir.path().join("tools/black/bin/black");
+        let ctx = make_context("black", vec![&bin_dir.join("black"), &target]);
+
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_id, "uv");
+        assert_eq!(result.package_name, Some("black".to_string()));
+        assert_eq!(result.version, Some("24.1.1".to_string()));
+        assert_eq!(result.confidence, Confidence::Medium);
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("The uncompromising code formatter.")
+        );
+        assert_eq!(info.provides, vec!["black", "blackd"]);
+        assert_eq!(info.extra["constraint"], ">=24");
+        assert_eq!(
+            info.extra["with"],
+            "tokenize-rt[cli], ipython @ git+https://github.com/ipython/ipython"
+        );
+        assert_eq!(info.extra["python_version"], "3.12.4");
+    }
+
+    #[test]
+    fn test_detects_copied_entrypoint_in_bin_dir() {
+        // Windows copies executables instead of linking them
+        let dir = tempfile::tempdir().unwrap();
+        let bin_dir = dir.path().join("bin");
+        let fixture = fixture(&bin_dir);
+        let detector = detector(fixture.path(), &bin_dir);
+
+        let ctx = make_context("blackd", vec![&bin_dir.join("blackd")]);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.package_name, Some("black".to_string()));
+
+        let other = make_context("ruff", vec![&bin_dir.join("ruff")]);
+        assert!(detector.detect(&other).is_none());
+    }
+
+    #[test]
+    fn test_legacy_string_requirements() {
+        let requirement = Requirement::Pep508("black[jupyter]>=24.1".to_string());
+        assert_eq!(requirement.name(), "black");
+        assert_eq!(requirement.constraint(), Some(">=24.1".to_string()));
+
+        let receipt: ReceiptFile =
+            toml::from_str("[tool]\nrequirements = [\"ruff\"]\nentrypoints = []\n").unwrap();
+        assert_eq!(receipt.tool.requirements[0].name(), "ruff");
+        assert_eq!(receipt.tool.requirements[0].constraint(), None);
+    }
+
+    #[test]
+    fn test_ignores_other_paths() {
+        let bin_dir = Path::new("/home/user/.local/bin");
+        let dir = fixture(bin_dir);
+        let ctx = make_context("black", vec![Path::new("/usr/bin/black")]);
+        assert!(detector(dir.path(), bin_dir).detect(&ctx).is_none());
+    }
+}
@@ -0,0 +1,403 @@
+//! Shim resolution for volta. Layout under `VOLTA_HOME` (default `~/.volta`):
+//!
+//! - `bin/<command>`: link to `volta-shim`, which picks the tool at run time
+//! - `tools/image/node/<version>`: an installed Node.js
+//! - `tools/image/packages/<package>`: an installed global package
+//! - `tools/user/platform.json`: the default Node version
+//! - `tools/user/bins/<command>.json`: the package and Node version behind a
+//!   global package's command
+//!
+//! Projects pin Node with a `"volta"` key in `package.json`.
+
+use super::node_global::{
+    node_install_info, node_install_result, node_version_detail, verify_node_install, NodeInstall,
+};
+use super::{DetectionContext, DetectionResult, PackageInfo, PackageManagerDetector, Verification};
+use crate::platform::Platform;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Commands shipped with Node itself, resolved through the selected Node version.
+const NODE_COMMANDS: &[&str] = &["node", "npm", "npx", "corepack"];
+
+/// Detector for volta-managed Node.js and global packages.
+pub struct VoltaDetector {
+    /// `VOLTA_HOME`, or `~/.volta` (`%LOCALAPPDATA%\Volta` on Windows)
+    volta_home: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+}
+
+/// What a volta shim or image path refers to.
+#[derive(Debug, PartialEq)]
+struct VoltaTarget {
+    install: NodeInstall,
+    /// Global package name and version, when the command comes from one
+    package: Option<(String, Option<String>)>,
+    resolved_path: PathBuf,
+    /// File that selected the Node version
+    origin: Option<String>,
+}
+
+impl VoltaDetector {
+    pub fn new() -> Self {
+        let volta_home = std::env::var_os("VOLTA_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                if cfg!(windows) {
+                    dirs::data_local_dir().map(|d| d.join("Volta"))
+                } else {
+                    dirs::home_dir().map(|home| home.join(".volta"))
+                }
+            });
+        Self {
+            volta_home,
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    /// The Node version for the current directory: the nearest project pin,
+    /// otherwise the default platform.
+    fn selected_node(&self, volta_home: &Path) -> Option<(String, String)> {
+        if let Some(ref cwd) = self.cwd {
+            for dir in cwd.ancestors() {
+                let manifest = dir.join("package.json");
+                if let Some(version) = pinned_node(&manifest) {
+                    return Some((version, manifest.display().to_string()));
+                }
+            }
+        }
+
+        let platform = volta_home.join("tools/user/platform.json");
+        let json = read_json(&platform)?;
+        let version = node_version(&json["node"])?;
+        Some((version, platform.display().to_string()))
+    }
+
+    fn node_install(&self, volta_home: &Path, version: &str) -> NodeInstall {
+        NodeInstall::new(volta_home.join("tools/image/node").join(version), version)
+    }
+
+    /// Resolve a shim the way `volta-shim` does.
+    fn resolve_shim(&self, volta_home: &Path, command: &str) -> Option<VoltaTarget> {
+        let bin_config = volta_home
+            .join("tools/user/bins")
+            .join(format!("{command}.json"));
+        if let Some(json) = read_json(&bin_config) {
+            // Global packages keep running on the Node they were installed with
+            let package = json["package"].as_str()?.to_string();
+            let node = node_version(&json["platform"]["node"])?;
+            let package_dir = volta_home.join("tools/image/packages").join(&package);
+            let binary = package_dir.join("bin").join(command);
+            return Some(VoltaTarget {
+                install: self.node_install(volta_home, &node),
+                resolved_path: binary.canonicalize().unwrap_or(binary),
+                package: Some((package, json["version"].as_str().map(str::to_string))),
+                origin: Some(bin_config.display().to_string()),
+            });
+        }
+
+        if !NODE_COMMANDS.contains(&command) {
+            return None;
+        }
+        let (node, origin) = self.selected_node(volta_home)?;
+        let install = self.node_install(volta_home, &node);
+        let binary = install.prefix.join("bin").join(command);
+        Some(VoltaTarget {
+            resolved_path: binary.canonicalize().unwrap_or(binary),
+            install,
+            package: None,
+            origin: Some(origin),
+        })
+    }
+
+    /// Recognise paths inside `tools/image/`.
+    fn resolve_image(&self, volta_home: &Path, path: &Path) -> Option<VoltaTarget> {
+        let relative = path.strip_prefix(volta_home.join("tools/image")).ok()?;
+        let mut parts = relative.iter().map(|p| p.to_string_lossy().to_string());
+        match parts.next()?.as_str() {
+            "node" => Some(VoltaTarget {
+                install: self.node_install(volta_home, &parts.next()?),
+                package: None,
+                resolved_path: path.to_path_buf(),
+                origin: None,
+            }),
+            "packages" => {
+                let mut package = parts.next()?;
+                if package.starts_with('@') {
+                    package = format!("{package}/{}", parts.next()?);
+                }
+                let config = volta_home
+                    .join("tools/user/packages")
+                    .join(format!("{package}.json"));
+                let json = read_json(&config)?;
+                Some(VoltaTarget {
+                    install: self
+                        .node_install(volta_home, &node_version(&json["platform"]["node"])?),
+                    package: Some((package, json["version"].as_str().map(str::to_string))),
+                    resolved_path: path.to_path_buf(),
+                    origin: None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn find_target(&self, ctx: &DetectionContext) -> Option<VoltaTarget> {
+        let volta_home = self.volta_home.as_ref()?;
+        let bin = volta_home.join("bin");
+
+        ctx.command_files().find_map(|path| {
+            if path.parent() == Some(bin.as_path()) {
+                let command = path.file_stem()?.to_string_lossy().to_string();
+                if command != "volta-shim" {
+                    return self.resolve_shim(volta_home, &command);
+                }
+            }
+            self.resolve_image(volta_home, path)
+        })
+    }
+}
+
+/// `"volta": {"node": ...}` from a `package.json`, following `extends`.
+fn pinned_node(manifest: &Path) -> Option<String> {
+    let mut manifest = manifest.to_path_buf();
+    // Bounded, in case of an `extends` cycle
+    for _ in 0..8 {
+        let json = read_json(&manifest)?;
+        let volta = json.get("volta")?;
+        if let Some(node) = volta["node"].as_str() {
+            return Some(node.to_string());
+        }
+        manifest = manifest.parent()?.join(volta["extends"].as_str()?);
+    }
+    None
+}
+
+/// A Node version as stored in volta's JSON files: a string, or an object
+/// with a `runtime` field in the older format.
+fn node_version(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value["runtime"].as_str())
+        .map(str::to_string)
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+impl PackageManagerDetector for VoltaDetector {
+    fn id(&self) -> &'static str {
+        "volta"
+    }
+
+    fn name(&self) -> &str {
+        "volta"
+    }
+
+    fn supports_platform(&self, _platform: Platform) -> bool {
+        true // volta is cross-platform
+    }
+
+    fn priority(&self) -> i32 {
+        95 // Same as n, ahead of npm (90) for global packages inside the image
+    }
+
+    fn detect(&self, ctx: &DetectionContext) -> Option<DetectionResult> {
+        let target = self.find_target(ctx)?;
+        let mut result = node_install_result(
+            ctx,
+            self.id(),
+            self.name(),
+            &target.install,
+            target.resolved_path,
+        );
+        if let Some((package, version)) = target.package {
+            result.package_name = Some(package);
+            result.version = version;
+            result.details = node_version_detail(&target.install);
+        }
+        Some(result)
+    }
+
+    fn verify(&self, ctx: &DetectionContext, result: &mut DetectionResult) -> Verification {
+        match self.find_target(ctx) {
+            Some(target) => verify_node_install(ctx, result, &target.install),
+            None => Verification::Unverified,
+        }
+    }
+
+    fn info(&self, ctx: &DetectionContext, result: &DetectionResult) -> Option<PackageInfo> {
+        let target = self.find_target(ctx)?;
+        let mut info = node_install_info(ctx, result, &target.install)?;
+        if let Some(origin) = target.origin {
+            info.extra.insert("selected_by".to_string(), origin);
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_context(command: &str, path: &Path) -> DetectionContext {
+        DetectionContext {
+            command_name: command.to_string(),
+            command_path: path.to_path_buf(),
+            symlink_chain: vec![path.to_path_buf()],
+            resolved_path: path.to_path_buf(),
+            platform: Platform::Linux,
+            script_links: Vec::new(),
+        }
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// A volta home with Node 20.11.0 as the default, Node 18.19.0 and a
+    /// global typescript installed with 18.19.0.
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join(".volta");
+        for version in ["18.19.0", "20.11.0"] {
+            write(
+                &home.join("tools/image/node").join(version).join("bin/node"),
+                "",
+            );
+        }
+        write(
+            &home.join("tools/user/platform.json"),
+            r#"{"node": {"runtime": "20.11.0", "npm": null}, "pnpm": null, "yarn": null}"#,
+        );
+        write(
+            &home.join("tools/user/bins/tsc.json"),
+            r#"{"name": "tsc", "package": "typescript", "version": "5.3.3",
+                "platform": {"node": "18.19.0", "npm": null, "yarn": null}, "manager": "Npm"}"#,
+        );
+        write(
+            &home.join("tools/image/packages/typescript/lib/node_modules/typescript/package.json"),
+            r#"{"name": "typescript", "version": "5.3.3", "bin": {"tsc": "./bin/tsc"}}"#,
+        );
+        write(&home.join("bin/volta-shim"), "");
+        write(&home.join("bin/node"), "");
+        write(&home.join("bin/tsc"), "");
+        std::fs::create_dir_all(dir.path().join("project/src")).unwrap();
+        dir
+    }
+
+    fn detector(dir: &Path, cwd: &Path) -> VoltaDetector {
+        VoltaDetector {
+            volta_home: Some(dir.join(".volta")),
+            cwd: Some(cwd.to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn test_default_platform() {
+        let dir = fixture();
+        let shim = dir.path().join(".volta/bin/node");
+        let detector = detector(dir.path(), dir.path());
+        let ctx = make_context("node", &shim);
+        let mut result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "volta");
+        assert_eq!(result.package_name, Some("node".to_string()));
+        assert_eq!(result.version, Some("20.11.0".to_string()));
+        assert_eq!(
+            result.resolved_path,
+            dir.path().join(".volta/tools/image/node/20.11.0/bin/node")
+        );
+        assert_eq!(detector.verify(&ctx, &mut result), Verification::Verified);
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert!(info.extra["selected_by"].ends_with("platform.json"));
+    }
+
+    #[test]
+    fn test_project_pin() {
+        let dir = fixture();
+        write(
+            &dir.path().join("project/package.json"),
+            r#"{"name": "app", "volta": {"node": "18.19.0"}}"#,
+        );
+        let shim = dir.path().join(".volta/bin/node");
+        let detector = detector(dir.path(), &dir.path().join("project/src"));
+        let result = detector.detect(&make_context("node", &shim)).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+    }
+
+    #[test]
+    fn test_project_pin_extends() {
+        let dir = fixture();
+        write(
+            &dir.path().join("project/package.json"),
+            r#"{"volta": {"node": "18.19.0"}}"#,
+        );
+        write(
+            &dir.path().join("project/src/package.json"),
+            r#"{"volta": {"extends": "../package.json"}}"#,
+        );
+        let shim = dir.path().join(".volta/bin/node");
+        let detector = detector(dir.path(), &dir.path().join("project/src"));
+        let result = detector.detect(&make_context("node", &shim)).unwrap();
+        assert_eq!(result.version, Some("18.19.0".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_global_package_shim() {
+        let dir = fixture();
+        // volta installs packages with `npm install --global --prefix <image>`
+        let image = dir.path().join(".volta/tools/image/packages/typescript");
+        write(&image.join("lib/node_modules/typescript/bin/tsc"), "");
+        std::fs::create_dir_all(image.join("bin")).unwrap();
+        std::os::unix::fs::symlink(
+            "../lib/node_modules/typescript/bin/tsc",
+            image.join("bin/tsc"),
+        )
+        .unwrap();
+
+        let shim = dir.path().join(".volta/bin/tsc");
+        let detector = detector(dir.path(), dir.path());
+        let ctx = make_context("tsc", &shim);
+        let result = detector.detect(&ctx).unwrap();
+        assert_eq!(result.manager_name, "volta");
+        assert_eq!(result.package_name, Some("typescript".to_string()));
+        assert_eq!(result.version, Some("5.3.3".to_string()));
+        assert_eq!(result.details["node_version"], "18.19.0");
+
+        let info = detector.info(&ctx, &result).unwrap();
+        assert_eq!(info.provides, vec!["tsc"]);
+    }
+
+    #[test]
+    fn test_package_image_path() {
+        let dir = fixture();
+        write(
+            &dir.path()
+                .join(".volta/tools/user/packages/typescript.json"),
+            r#"{"name": "typescript", "version": "5.3.3", "platform": {"node": {"runtime": "18.19.0"}}, "bins": ["tsc"]}"#,
+        );
+        let path = dir
+            .path()
+            .join(".volta/tools/image/packages/typescript/lib/node_modules/typescript/bin/tsc");
+        let result = detector(dir.path(), dir.path())
+            .detect(&make_context("tsc", &path))
+            .unwrap();
+        assert_eq!(result.package_name, Some("typescript".to_string()));
+        assert_eq!(result.manager_name, "volta");
+        assert_eq!(result.details["node_version"], "18.19.0");
+    }
+
+    #[test]
+    fn test_unknown_shim() {
+        let dir = fixture();
+        let shim = dir.path().join(".volta/bin/unknown");
+        assert!(detector(dir.path(), dir.path())
+            .detect(&make_context("unknown", &shim))
+            .is_none());
+    }
+}
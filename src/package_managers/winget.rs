@@ -1,4 +1,4 @@
-use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector};
+use super::{Confidence, DetectionContext, DetectionResult, PackageManagerDetector, Verification};
 use crate::platform::Platform;
 
 /// Detector for Winget (Windows Package Manager) packages.
@@ -43,7 +43,10 @@ impl PackageManagerDetector for WingetDetector {
                     manager_name: self.name().to_string(),
                     package_name: Some(ctx.command_name.clone()),
                     version: None,
+                    details: Default::default(),
                     confidence: Confidence::Medium,
+                    verification: Verification::Unverified,
+                    info: None,
                     command_path: ctx.command_path.clone(),
                     resolved_path: ctx.resolved_path.clone(),
                 });
@@ -68,6 +71,7 @@ mod tests {
             symlink_chain: paths.iter().map(PathBuf::from).collect(),
             resolved_path,
             platform,
+            script_links: Vec::new(),
         }
     }
 
@@ -0,0 +1,160 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn why() -> Command {
+    Command::cargo_bin("why").unwrap()
+}
+
+#[test]
+fn test_requires_a_command() {
+    why().assert().failure();
+}
+
+#[test]
+fn test_unknown_command_fails() {
+    why()
+        .arg("definitely_not_a_real_command_xyz_123")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found in PATH"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_single_command_json_is_an_object() {
+    let output = why().args(["--json", "ls"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.is_object());
+    assert!(json["manager_id"].is_string());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_multiple_commands_json_is_an_array() {
+    let output = why().args(["--json", "ls", "sh"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reports = json.as_array().unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0]["command"], "ls");
+    assert_eq!(reports[1]["command"], "sh");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_multiple_commands_short_is_tab_separated() {
+    why()
+        .args(["-f", "short", "ls", "sh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^ls\t\S+\nsh\t\S+\n$").unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_multiple_commands_fail_if_any_is_missing() {
+    let output = why()
+        .args(["--json", "ls", "definitely_not_a_real_command_xyz_123"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json[0]["manager_id"].is_string());
+    assert!(json[1]["error"]
+        .as_str()
+        .unwrap()
+        .contains("definitely_not_a_real_command_xyz_123"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_rpm_detector_uses_rpm_on_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Stand-in `rpm` that claims ownership of every file
+    let dir = tempfile::tempdir().unwrap();
+    let rpm = dir.path().join("rpm");
+    std::fs::write(
+        &rpm,
+        "#!/bin/sh\nprintf 'coreutils\\t9.3-4.fc39\\tx86_64\\n'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&rpm, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!("{}:/usr/bin:/bin", dir.path().display());
+    let rpm_match = |extra: &[&str]| -> serde_json::Value {
+        let output = why()
+            .env("PATH", &path)
+            .args(extra)
+            .args(["--all-matches", "--json", "ls"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["manager_id"] == "rpm")
+            .unwrap()
+            .clone()
+    };
+
+    let rpm = rpm_match(&[]);
+    assert_eq!(rpm["package_name"], "coreutils");
+    assert_eq!(rpm["version"], "9.3-4.fc39");
+    assert_eq!(rpm["details"]["architecture"], "x86_64");
+    assert_eq!(rpm["verification"], "verified");
+
+    // Without verification rpm is never run
+    let rpm = rpm_match(&["--no-verify"]);
+    assert!(rpm["package_name"].is_null());
+    assert_eq!(rpm["confidence"], "medium");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_no_verify_keeps_cargo_match_unverified() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cargo_home = tempfile::tempdir().unwrap();
+    let bin = cargo_home.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let rg = bin.join("rg");
+    std::fs::write(&rg, "#!/bin/sh\n").unwrap();
+    std::fs::set_permissions(&rg, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        cargo_home.path().join(".crates2.json"),
+        r#"{"installs":{"ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)":{"bins":["rg"]}}}"#,
+    )
+    .unwrap();
+
+    let run = |extra: &[&str]| -> serde_json::Value {
+        let output = why()
+            .env("CARGO_HOME", cargo_home.path())
+            .env("PATH", format!("{}:/usr/bin:/bin", bin.display()))
+            .args(extra)
+            .args(["--json", "rg"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let verified = run(&[]);
+    assert_eq!(verified["manager_id"], "cargo");
+    assert_eq!(verified["package_name"], "ripgrep");
+    assert_eq!(verified["confidence"], "high");
+    assert_eq!(verified["verification"], "verified");
+
+    let unverified = run(&["--no-verify"]);
+    assert_eq!(unverified["manager_id"], "cargo");
+    assert_eq!(unverified["package_name"], "rg");
+    assert_eq!(unverified["confidence"], "medium");
+    assert_eq!(unverified["verification"], "unverified");
+}
+
+#[test]
+fn test_inventory_conflicts_with_commands() {
+    why().args(["--inventory", "git"]).assert().failure();
+}